@@ -0,0 +1,270 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! Aliases, which run one or more console commands under a single name.
+//!
+//! They're defined in the config file, for every server or for just one:
+//!
+//! ```toml
+//! [aliases.nextmap]
+//! description = "Warn everyone and change the map"
+//! steps = ["say Changing to $1 in 10 seconds", { wait = 10 }, "map $1"]
+//! ```
+//!
+//! In a command, `$1` to `$9` are replaced with the arguments the alias was called with,
+//! `$*` with all of them and `$$` with a single `$`.
+
+use crate::config::{Alias, AliasStep, Config, ServerConfig};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AliasError {
+    #[error("{name} needs {needed} argument(s), but got {given}")]
+    MissingArguments {
+        name: String,
+        needed: usize,
+        given: usize,
+    },
+    #[error("{name} has an invalid wait of {seconds} seconds")]
+    InvalidWait { name: String, seconds: f64 },
+}
+
+/// A step of an alias, with the arguments filled in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Command(String),
+    Wait(Duration),
+}
+
+/// Finds an alias by name, ignoring case like the console does. The server's own
+/// aliases are looked at first.
+pub fn find<'a>(
+    config: &'a Config,
+    server: Option<&'a ServerConfig>,
+    name: &str,
+) -> Option<&'a Alias> {
+    let find_in = |aliases: &'a BTreeMap<String, Alias>| {
+        aliases
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+            .map(|(_, alias)| alias)
+    };
+    server
+        .and_then(|server| find_in(&server.aliases))
+        .or_else(|| find_in(&config.aliases))
+}
+
+/// Splits the arguments an alias was called with at spaces, keeping quoted arguments
+/// together. The quotes are kept so the server sees them too.
+pub fn split_args(input: &str) -> Vec<&str> {
+    let mut args = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (i, c) in input.char_indices() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                start.get_or_insert(i);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if let Some(start) = start.take() {
+                    args.push(&input[start..i]);
+                }
+            }
+            _ => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(start) = start {
+        args.push(&input[start..]);
+    }
+    args
+}
+
+/// Replaces the placeholders in a command, returning the highest argument it used.
+fn substitute(command: &str, args: &[&str]) -> (String, usize) {
+    let mut result = String::with_capacity(command.len());
+    let mut highest = 0;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+        match chars.peek().copied() {
+            Some('$') => result.push('$'),
+            Some('*') => result.push_str(&args.join(" ")),
+            Some(digit @ '1'..='9') => {
+                // unwrap is safe, it's a digit
+                let n = digit.to_digit(10).unwrap() as usize;
+                highest = highest.max(n);
+                result.push_str(args.get(n - 1).unwrap_or(&""));
+            }
+            _ => {
+                result.push('$');
+                continue;
+            }
+        }
+        chars.next();
+    }
+    (result, highest)
+}
+
+fn wait(name: &str, seconds: f64) -> Result<Duration, AliasError> {
+    Duration::try_from_secs_f64(seconds).map_err(|_| AliasError::InvalidWait {
+        name: name.to_string(),
+        seconds,
+    })
+}
+
+/// Fills in the arguments of an alias, adding its delay between the commands.
+pub fn expand(name: &str, alias: &Alias, args: &[&str]) -> Result<Vec<Step>, AliasError> {
+    let delay = wait(name, alias.delay)?;
+    let mut steps = Vec::new();
+    let mut needed = 0;
+    for step in &alias.steps {
+        match step {
+            AliasStep::Command(command) => {
+                if !delay.is_zero() && steps.iter().any(|step| matches!(step, Step::Command(_))) {
+                    steps.push(Step::Wait(delay));
+                }
+                let (command, highest) = substitute(command, args);
+                needed = needed.max(highest);
+                steps.push(Step::Command(command));
+            }
+            AliasStep::Wait(w) => steps.push(Step::Wait(wait(name, w.wait)?)),
+        }
+    }
+    if args.len() < needed {
+        return Err(AliasError::MissingArguments {
+            name: name.to_string(),
+            needed,
+            given: args.len(),
+        });
+    }
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Wait;
+
+    fn alias(steps: &[&str]) -> Alias {
+        Alias {
+            steps: steps
+                .iter()
+                .map(|step| AliasStep::Command(step.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn split_arguments() {
+        assert_eq!(
+            split_args(" MAP01  \"hello there\" x"),
+            vec!["MAP01", "\"hello there\"", "x"]
+        );
+        assert_eq!(split_args(""), Vec::<&str>::new());
+        assert_eq!(
+            split_args("\"unterminated quote"),
+            vec!["\"unterminated quote"]
+        );
+    }
+
+    #[test]
+    fn placeholders() {
+        let steps = expand(
+            "test",
+            &alias(&["say $2 $1", "say $*", "say $$1 costs $5"]),
+            &["a", "b", "c", "d", "e"],
+        )
+        .unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                Step::Command("say b a".to_string()),
+                Step::Command("say a b c d e".to_string()),
+                Step::Command("say $1 costs e".to_string()),
+            ]
+        );
+        assert_eq!(
+            expand("test", &alias(&["map $2"]), &["MAP01"]),
+            Err(AliasError::MissingArguments {
+                name: "test".to_string(),
+                needed: 2,
+                given: 1
+            })
+        );
+        // a $ that isn't a placeholder is left alone
+        assert_eq!(
+            expand("test", &alias(&["say $x$"]), &[]).unwrap(),
+            vec![Step::Command("say $x$".to_string())]
+        );
+    }
+
+    #[test]
+    fn waits_and_delays() {
+        let mut alias = alias(&["say one", "say two", "say three"]);
+        alias.steps.insert(1, AliasStep::Wait(Wait { wait: 2.0 }));
+        alias.delay = 0.5;
+        let steps = expand("test", &alias, &[]).unwrap();
+        let half = Duration::from_millis(500);
+        assert_eq!(
+            steps,
+            vec![
+                Step::Command("say one".to_string()),
+                Step::Wait(Duration::from_secs(2)),
+                Step::Wait(half),
+                Step::Command("say two".to_string()),
+                Step::Wait(half),
+                Step::Command("say three".to_string()),
+            ]
+        );
+        alias.delay = -1.0;
+        assert!(matches!(
+            expand("test", &alias, &[]),
+            Err(AliasError::InvalidWait { .. })
+        ));
+    }
+
+    #[test]
+    fn server_aliases_come_first() {
+        let config = Config {
+            aliases: BTreeMap::from([
+                ("restart".to_string(), alias(&["global"])),
+                ("other".to_string(), alias(&["other"])),
+            ]),
+            ..Config::empty()
+        };
+        let server = ServerConfig {
+            aliases: BTreeMap::from([("Restart".to_string(), alias(&["server"]))]),
+            ..Default::default()
+        };
+        assert_eq!(
+            find(&config, Some(&server), "RESTART"),
+            Some(&alias(&["server"]))
+        );
+        assert_eq!(find(&config, None, "restart"), Some(&alias(&["global"])));
+        assert_eq!(
+            find(&config, Some(&server), "other"),
+            Some(&alias(&["other"]))
+        );
+        assert_eq!(find(&config, Some(&server), "nope"), None);
+    }
+}
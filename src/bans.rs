@@ -0,0 +1,299 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! The server's ban list and the commands that change it.
+//!
+//! The list is read from what `banlist` prints, one line per ban with the expiry in UTC:
+//!
+//! ```text
+//! (01): 10.0.0.* - expires: 2026-10-20 18:00:00 - reason: cheating
+//! (02): 192.168.1.20 - expires: never - reason:
+//! ```
+//!
+//! Bans are added with `addban <ip/mask> <length> [reason]` and removed by their number
+//! with `delban`. There's no command for changing a ban, so editing one removes it and
+//! adds it again.
+
+use crate::client::{RCONError, RCONSocket};
+use crate::command::quote;
+use std::cmp::Ordering;
+use std::time::Duration;
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+/// How long `banlist` has to be quiet before the list is taken to be complete.
+const LIST_QUIET_TIME: Duration = Duration::from_millis(500);
+
+const EXPIRY_FORMAT: &[time::format_description::BorrowedFormatItem<'static>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ban {
+    /// The number `delban` takes
+    pub index: usize,
+    /// An IPv4 address, parts of it can be `*` to ban a range
+    pub address: String,
+    /// When the ban runs out in UTC, `None` for bans that don't
+    pub expires: Option<PrimitiveDateTime>,
+    pub reason: String,
+}
+
+impl Ban {
+    /// Parses a line of the ban list, `None` for lines that aren't a ban.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (index, rest) = line.trim().strip_prefix('(')?.split_once("):")?;
+        let index = index.trim().parse().ok()?;
+        let (address, rest) = rest.split_once(" - expires: ")?;
+        let (expires, reason) = rest.split_once(" - reason:").unwrap_or((rest, ""));
+        let expires = match expires.trim() {
+            "never" => None,
+            expires => Some(PrimitiveDateTime::parse(expires, EXPIRY_FORMAT).ok()?),
+        };
+        Some(Self {
+            index,
+            address: address.trim().to_string(),
+            expires,
+            reason: reason.trim().to_string(),
+        })
+    }
+
+    /// When the ban runs out, for showing in a list.
+    pub fn expires_label(&self) -> String {
+        match self.expires {
+            Some(expires) => format_expiry(expires),
+            None => "never".to_string(),
+        }
+    }
+
+    /// How much of the ban is left as a length [`add_command`] takes, rounded up to the
+    /// minute. Empty for bans that don't run out.
+    pub fn remaining(&self, now: OffsetDateTime) -> String {
+        let Some(expires) = self.expires else {
+            return String::new();
+        };
+        let seconds = (expires.assume_utc() - now).whole_seconds().max(60);
+        let minutes = (seconds + 59) / 60;
+        let (count, unit) = if minutes % (60 * 24) == 0 {
+            (minutes / (60 * 24), "day")
+        } else if minutes % 60 == 0 {
+            (minutes / 60, "hour")
+        } else {
+            (minutes, "minute")
+        };
+        if count == 1 {
+            format!("1 {unit}")
+        } else {
+            format!("{count} {unit}s")
+        }
+    }
+}
+
+/// Formats an expiry the way the ban list does.
+pub fn format_expiry(expires: PrimitiveDateTime) -> String {
+    expires
+        .format(EXPIRY_FORMAT)
+        .unwrap_or_else(|_| expires.to_string())
+}
+
+/// Reads every ban from the output of `banlist`.
+pub fn parse_bans(output: &str) -> Vec<Ban> {
+    output.lines().filter_map(Ban::parse).collect()
+}
+
+/// Asks the server for its ban list.
+pub async fn fetch(socket: &RCONSocket) -> Result<Vec<Ban>, RCONError> {
+    let output = socket.command_output("banlist", LIST_QUIET_TIME).await?;
+    Ok(parse_bans(&output))
+}
+
+/// Whether the address is something the server can ban: an IPv4 address where any
+/// part can be `*`.
+pub fn valid_mask(address: &str) -> bool {
+    let parts: Vec<&str> = address.split('.').collect();
+    parts.len() == 4
+        && parts
+            .iter()
+            .all(|part| *part == "*" || part.parse::<u8>().is_ok())
+}
+
+/// The length and reason arguments of `ban` and `addban`. An empty length bans for good.
+pub(crate) fn length_and_reason(length: &str, reason: &str) -> String {
+    let length = match length.trim() {
+        "" => "permanent",
+        length => length,
+    };
+    let reason = reason.trim();
+    if reason.is_empty() {
+        quote(length)
+    } else {
+        format!("{} {}", quote(length), quote(reason))
+    }
+}
+
+/// The command that bans the address, see [`length_and_reason`] for the rest.
+pub fn add_command(address: &str, length: &str, reason: &str) -> String {
+    format!(
+        "addban {} {}",
+        address.trim(),
+        length_and_reason(length, reason)
+    )
+}
+
+/// The command that lifts the ban with the number.
+pub fn remove_command(index: usize) -> String {
+    format!("delban {index}")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BanColumn {
+    #[default]
+    Address,
+    Reason,
+    Expires,
+}
+
+/// How a ban list is sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BanOrder {
+    pub column: BanColumn,
+    pub descending: bool,
+}
+
+/// Sorts addresses by their numbers rather than as text, with `*` after any number.
+fn address_key(address: &str) -> Vec<u16> {
+    address
+        .split('.')
+        .map(|part| part.parse().unwrap_or(u16::MAX))
+        .collect()
+}
+
+impl BanOrder {
+    /// Sorts by the column, or the other way around if it's sorted by it already.
+    pub fn toggle(self, column: BanColumn) -> Self {
+        Self {
+            column,
+            descending: self.column == column && !self.descending,
+        }
+    }
+
+    /// Sorts the bans, bans that don't run out come after the ones that do.
+    pub fn sort(self, bans: &mut [Ban]) {
+        bans.sort_by(|a, b| {
+            let ordering = match self.column {
+                BanColumn::Address => address_key(&a.address).cmp(&address_key(&b.address)),
+                BanColumn::Reason => a.reason.to_lowercase().cmp(&b.reason.to_lowercase()),
+                BanColumn::Expires => match (a.expires, b.expires) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                },
+            }
+            .then(a.index.cmp(&b.index));
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn ban(index: usize, address: &str, expires: Option<PrimitiveDateTime>, reason: &str) -> Ban {
+        Ban {
+            index,
+            address: address.to_string(),
+            expires,
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_listing() {
+        let output = "(01): 10.0.0.* - expires: 2026-10-20 18:00:00 - reason: cheating - again\n\
+                      Ban list is empty\n\
+                      (02): 192.168.1.20 - expires: never - reason:\n\
+                      (03): 1.2.3.4 - expires: soon - reason:\n";
+        assert_eq!(
+            parse_bans(output),
+            vec![
+                ban(
+                    1,
+                    "10.0.0.*",
+                    Some(datetime!(2026-10-20 18:00:00)),
+                    "cheating - again"
+                ),
+                ban(2, "192.168.1.20", None, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(add_command("10.0.0.*", "", ""), "addban 10.0.0.* permanent");
+        assert_eq!(
+            add_command(" 1.2.3.4 ", "2 days", "team killing"),
+            "addban 1.2.3.4 \"2 days\" \"team killing\""
+        );
+        assert_eq!(remove_command(3), "delban 3");
+        assert!(valid_mask("10.0.*.*"));
+        assert!(!valid_mask("10.0.0"));
+        assert!(!valid_mask("10.0.0.256"));
+        assert!(!valid_mask("example.com"));
+    }
+
+    #[test]
+    fn remaining_length() {
+        let now = datetime!(2026-10-16 12:00:00 UTC);
+        let remaining = |expires| ban(1, "1.2.3.4", expires, "").remaining(now);
+        assert_eq!(remaining(None), "");
+        assert_eq!(remaining(Some(datetime!(2026-10-18 12:00:00))), "2 days");
+        assert_eq!(remaining(Some(datetime!(2026-10-16 13:00:00))), "1 hour");
+        assert_eq!(
+            remaining(Some(datetime!(2026-10-16 13:30:10))),
+            "91 minutes"
+        );
+        // a ban that ran out but is still listed is kept for a minute
+        assert_eq!(remaining(Some(datetime!(2026-10-16 11:00:00))), "1 minute");
+    }
+
+    #[test]
+    fn sorting() {
+        let mut bans = vec![
+            ban(1, "10.0.0.*", None, "b"),
+            ban(2, "9.1.1.1", Some(datetime!(2026-10-20 0:00)), "A"),
+            ban(3, "10.0.0.5", Some(datetime!(2026-10-17 0:00)), "c"),
+        ];
+        let indexes = |bans: &[Ban]| -> Vec<usize> { bans.iter().map(|ban| ban.index).collect() };
+        let order = BanOrder::default();
+        order.sort(&mut bans);
+        assert_eq!(indexes(&bans), vec![2, 3, 1]);
+        let order = order.toggle(BanColumn::Address);
+        order.sort(&mut bans);
+        assert_eq!(indexes(&bans), vec![1, 3, 2]);
+        let order = order.toggle(BanColumn::Expires);
+        assert!(!order.descending);
+        order.sort(&mut bans);
+        assert_eq!(indexes(&bans), vec![3, 2, 1]);
+        BanOrder::default()
+            .toggle(BanColumn::Reason)
+            .sort(&mut bans);
+        assert_eq!(indexes(&bans), vec![2, 1, 3]);
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use clap::Parser;
+use odarcon::mock::{DROP_COMMAND, MockConfig, MockServer};
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Fake Odamex server for trying out odarcon without a real one.
+/// Commands are echoed back, and sending "mock_drop" drops the connection.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, short, default_value = "127.0.0.1:11666")]
+    bind: SocketAddr,
+    /// RCON password clients have to log in with
+    #[arg(
+        long,
+        env = "ODARCON_PASSWORD",
+        hide_env_values = true,
+        default_value = ""
+    )]
+    password: String,
+    /// Seconds between scripted prints, without it they're all sent once right after login
+    #[arg(long, short, value_parser = clap::value_parser!(u64).range(1..))]
+    interval: Option<u64>,
+    /// Drop every connection this many seconds apart, to try out reconnecting
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    drop_every: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    let config = MockConfig {
+        password: args.password,
+        script_interval: args.interval.map(Duration::from_secs),
+        ..Default::default()
+    };
+    let server = match MockServer::start(args.bind, config).await {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Could not listen on {}: {e}", args.bind);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "Mock server listening on {}, send \"{DROP_COMMAND}\" to drop the connection",
+        server.local_addr()
+    );
+
+    let drop_timer = async {
+        match args.drop_every {
+            Some(seconds) => {
+                let mut timer = tokio::time::interval(Duration::from_secs(seconds));
+                // the first tick is immediate
+                timer.tick().await;
+                loop {
+                    timer.tick().await;
+                    println!("Dropping all connections");
+                    server.drop_connections();
+                }
+            }
+            None => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = drop_timer => {}
+    }
+    server.shutdown();
+    ExitCode::SUCCESS
+}
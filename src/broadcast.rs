@@ -0,0 +1,188 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! Sending one command to several servers at once.
+//!
+//! Every server gets a connection of its own that is closed again once the command's
+//! output is in, so a broadcast doesn't depend on what's open in the console.
+//! Servers can be picked by name or by the tags given to them in the config file:
+//!
+//! ```toml
+//! [[servers]]
+//! name = "EU Duel"
+//! tags = ["eu", "duel"]
+//! ```
+
+use crate::client::{ConnectionEvent, ConnectionState, RCONError, RCONSocket};
+use crate::config::ServerConfig;
+use futures_util::future::join_all;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use thiserror::Error;
+
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum BroadcastError {
+    #[error("{0}")]
+    Rcon(#[from] RCONError),
+    #[error("Login failed: {0}")]
+    LoginFailed(String),
+    #[error("Timed out logging in")]
+    LoginTimeout,
+    #[error("{0}")]
+    Closed(String),
+}
+
+/// What a server printed in response to the command, or why it couldn't run it.
+#[derive(Debug)]
+pub struct Outcome {
+    pub server: String,
+    pub output: Result<String, BroadcastError>,
+}
+
+/// Picks the servers that are named or have any of the tags, ignoring case. They stay in
+/// the order of the config file and each is only picked once.
+pub fn select<'a>(
+    servers: &'a [ServerConfig],
+    names: &[String],
+    tags: &[String],
+) -> Vec<&'a ServerConfig> {
+    servers
+        .iter()
+        .filter(|server| {
+            names
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&server.name))
+                || tags
+                    .iter()
+                    .any(|tag| server.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        })
+        .collect()
+}
+
+/// Every tag used by the servers, sorted and without duplicates that only differ in case.
+pub fn all_tags(servers: &[ServerConfig]) -> Vec<String> {
+    let tags: BTreeMap<String, &String> = servers
+        .iter()
+        .flat_map(|server| &server.tags)
+        .map(|tag| (tag.to_lowercase(), tag))
+        .collect();
+    tags.into_values().cloned().collect()
+}
+
+async fn login(socket: &RCONSocket) -> Result<(), BroadcastError> {
+    let mut events = socket.subscribe();
+    let mut state = socket.state();
+    let state = tokio::time::timeout(
+        LOGIN_TIMEOUT,
+        state.wait_for(|state| {
+            !matches!(
+                state,
+                ConnectionState::Connecting | ConnectionState::LoggingIn
+            )
+        }),
+    )
+    .await
+    .map_err(|_| BroadcastError::LoginTimeout)?
+    .map(|state| state.clone());
+    match state {
+        Ok(ConnectionState::LoggedIn) => Ok(()),
+        Ok(ConnectionState::LoginFailed(reason)) => Err(BroadcastError::LoginFailed(reason)),
+        _ => {
+            let mut reason = "Connection was closed".to_string();
+            while let Ok(event) = events.try_recv() {
+                if let ConnectionEvent::Closed { reason: r } = event {
+                    reason = r.to_string();
+                }
+            }
+            Err(BroadcastError::Closed(reason))
+        }
+    }
+}
+
+/// Connects to the server, runs the command and disconnects again. The output is
+/// collected like [`RCONSocket::command_output`] does.
+pub async fn run_on(
+    server: &ServerConfig,
+    command: &str,
+    quiet: Duration,
+) -> Result<String, BroadcastError> {
+    let server = ServerConfig {
+        reconnect: false,
+        ..server.clone()
+    };
+    let socket = RCONSocket::connect(&server)?;
+    let output = match login(&socket).await {
+        Ok(()) => socket
+            .command_output(command, quiet)
+            .await
+            .map_err(BroadcastError::from),
+        Err(e) => Err(e),
+    };
+    socket.disconnect();
+    output
+}
+
+/// Runs the command on all the servers at the same time. The outcomes are in the same
+/// order as the servers.
+pub async fn broadcast(servers: &[ServerConfig], command: &str, quiet: Duration) -> Vec<Outcome> {
+    join_all(servers.iter().map(|server| async move {
+        Outcome {
+            server: server.name.clone(),
+            output: run_on(server, command, quiet).await,
+        }
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(name: &str, tags: &[&str]) -> ServerConfig {
+        ServerConfig {
+            name: name.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn select_by_name_and_tag() {
+        let servers = [
+            server("EU Duel", &["eu", "Duel"]),
+            server("US CTF", &["us"]),
+            server("EU CTF", &["EU"]),
+        ];
+        let names = |selected: Vec<&ServerConfig>| -> Vec<String> {
+            selected.iter().map(|server| server.name.clone()).collect()
+        };
+        assert_eq!(
+            names(select(&servers, &[], &["eu".to_string()])),
+            vec!["EU Duel", "EU CTF"]
+        );
+        // a server that's both named and tagged is only picked once
+        assert_eq!(
+            names(select(
+                &servers,
+                &["eu ctf".to_string(), "us ctf".to_string()],
+                &["duel".to_string(), "eu".to_string()]
+            )),
+            vec!["EU Duel", "US CTF", "EU CTF"]
+        );
+        assert!(select(&servers, &["nope".to_string()], &[]).is_empty());
+        assert_eq!(all_tags(&servers), vec!["Duel", "EU", "us"]);
+    }
+}
@@ -0,0 +1,742 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! The websocket connection to a server, including the login handshake.
+
+use crate::config::ServerConfig;
+use crate::protocol;
+use crate::protocol::{
+    ClientMessage, ClientMessageType, MaplistEntry, PrintLevel, ServerInfo, ServerMessage,
+    ServerMessageType, UNSOLICITED_ID,
+};
+use crate::tls;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::{broadcast, watch};
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::{Connector, connect_async_tls_with_config};
+use tokio_util::sync::CancellationToken;
+
+const EVENT_CAPACITY: usize = 1024;
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum RCONError {
+    #[error("Websocket error: {0}")]
+    WebsocketError(#[from] tungstenite::Error),
+    #[error("{0}")]
+    TlsError(#[from] tls::TlsError),
+    #[error("Timed out waiting for a reply from the server")]
+    Timeout,
+    #[error("Not connected to the server")]
+    Disconnected,
+}
+
+/// Requests that are waiting for a reply, by message id.
+type PendingRequests = Arc<Mutex<HashMap<usize, UnboundedSender<ServerMessage>>>>;
+
+/// Keeps a request registered for as long as someone is waiting on it,
+/// even if the waiting future gets dropped early.
+struct PendingGuard {
+    pending: PendingRequests,
+    id: usize,
+}
+
+impl PendingGuard {
+    fn register(pending: &PendingRequests, id: usize) -> (Self, UnboundedReceiver<ServerMessage>) {
+        let (reply_tx, reply_rx) = tokio::sync::mpsc::unbounded_channel();
+        pending.lock().unwrap().insert(id, reply_tx);
+        let guard = Self {
+            pending: pending.clone(),
+            id,
+        };
+        (guard, reply_rx)
+    }
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(&self.id);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    LoggingIn,
+    LoggedIn,
+    LoginFailed(String),
+    Reconnecting { attempt: u32 },
+    Closed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloseReason {
+    /// The websocket connection could not be established
+    ConnectFailed(String),
+    /// The server sent a close frame, with its reason if it gave one
+    ClosedByServer(Option<String>),
+    /// The connection dropped without a close frame
+    ConnectionLost(String),
+    /// The server rejected the login
+    LoginFailed(String),
+    /// We closed the connection ourselves
+    Disconnected,
+}
+
+impl std::fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloseReason::ConnectFailed(e) => write!(f, "Failed to connect: {}", e),
+            CloseReason::ClosedByServer(Some(why)) => {
+                write!(f, "Connection closed by server: {}", why)
+            }
+            CloseReason::ClosedByServer(None) => write!(f, "Connection closed by server"),
+            CloseReason::ConnectionLost(e) => {
+                write!(f, "Connection to server has been lost: {}", e)
+            }
+            CloseReason::LoginFailed(why) => write!(f, "Login failed: {}", why),
+            CloseReason::Disconnected => write!(f, "Disconnected from server"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    Connecting,
+    Connected,
+    Authenticated,
+    AuthFailed(String),
+    Print {
+        level: PrintLevel,
+        text: String,
+    },
+    /// What the server is running, if it said so when logging in
+    ServerInfo(ServerInfo),
+    /// The server pushed its maplist without being asked, usually because it changed
+    Maplist(Vec<MaplistEntry>),
+    /// The server sent something that isn't a valid message
+    ProtocolError {
+        raw: String,
+        error: String,
+    },
+    SendError(String),
+    /// The connection dropped and another attempt will be made after `delay`
+    Reconnecting {
+        attempt: u32,
+        delay: Duration,
+        reason: CloseReason,
+    },
+    Closed {
+        reason: CloseReason,
+    },
+}
+
+/// Replies to a single request, see [`RCONSocket::request_replies`].
+pub struct Replies {
+    _guard: PendingGuard,
+    rx: UnboundedReceiver<ServerMessage>,
+}
+
+impl Replies {
+    /// Waits for the next reply, or returns `None` once the connection is closed.
+    pub async fn next(&mut self) -> Option<ServerMessage> {
+        self.rx.recv().await
+    }
+}
+
+/// A handle to a connection, cloning it gives another handle to the same connection.
+#[derive(Clone)]
+pub struct RCONSocket {
+    tx: UnboundedSender<String>,
+    next_id: Arc<AtomicUsize>,
+    pending: PendingRequests,
+    events: broadcast::Sender<ConnectionEvent>,
+    state: watch::Receiver<ConnectionState>,
+    cancel_token: CancellationToken,
+}
+
+impl RCONSocket {
+    pub fn connect(server: &ServerConfig) -> Result<Self, RCONError> {
+        let (scheme, connector) = match &server.tls {
+            Some(tls) => ("wss", Connector::Rustls(tls::client_config(tls)?)),
+            None => ("ws", Connector::Plain),
+        };
+        let url_str = format!("{}://{}:{}", scheme, server.host, server.port);
+        // TODO: better error handling here, this likely wont result in a good error
+        let mut req = url_str.into_client_request()?;
+        req.headers_mut()
+            .append("Sec-WebSocket-Protocol", "odamex-rcon".parse().unwrap()); // unwrap is safe with only ascii
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let (state_tx, state) = watch::channel(ConnectionState::Connecting);
+        let cancel_token = CancellationToken::new();
+        let next_id = Arc::new(AtomicUsize::new(UNSOLICITED_ID + 1));
+        let pending = PendingRequests::default();
+        let session = Session {
+            next_id: next_id.clone(),
+            pending: pending.clone(),
+            password: server.password.clone(),
+            version: server.protoversion.into(),
+            reconnect: server.reconnect,
+            connector,
+            events: events.clone(),
+            state: state_tx,
+            cancel_token: cancel_token.clone(),
+        };
+        tokio::spawn(session.run(req, rx));
+        Ok(Self {
+            tx,
+            next_id,
+            pending,
+            events,
+            state,
+            cancel_token,
+        })
+    }
+
+    /// Sends a message without waiting for a reply, and returns the id it was sent with.
+    /// Messages sent while reconnecting are dropped with a [`ConnectionEvent::SendError`]
+    /// rather than sent once the connection is back.
+    pub fn send(&self, content: ClientMessageType) -> usize {
+        let message = ClientMessage::new(self.next_id.fetch_add(1, Ordering::Relaxed), content);
+        if let Err(e) = self.tx.send(message.serialize()) {
+            let _ = self.events.send(ConnectionEvent::SendError(format!(
+                "Failed to send message: {}",
+                e
+            )));
+        }
+        message.id()
+    }
+
+    /// Sends a message and waits for the server's reply to it.
+    /// The reply goes only to the caller, it is not broadcast to subscribers.
+    pub async fn request(&self, content: ClientMessageType) -> Result<ServerMessage, RCONError> {
+        let mut replies = self.request_replies(content)?;
+        match tokio::time::timeout(REQUEST_TIMEOUT, replies.next()).await {
+            Ok(Some(reply)) => Ok(reply),
+            Ok(None) => Err(RCONError::Disconnected),
+            Err(_) => Err(RCONError::Timeout),
+        }
+    }
+
+    /// Sends a message and returns a stream of every reply to it, for commands that can
+    /// print any number of lines. Like with [`RCONSocket::request`], the replies are not
+    /// broadcast to subscribers.
+    pub fn request_replies(&self, content: ClientMessageType) -> Result<Replies, RCONError> {
+        let message = ClientMessage::new(self.next_id.fetch_add(1, Ordering::Relaxed), content);
+        let (guard, rx) = PendingGuard::register(&self.pending, message.id());
+        self.tx
+            .send(message.serialize())
+            .map_err(|_| RCONError::Disconnected)?;
+        Ok(Replies { _guard: guard, rx })
+    }
+
+    /// Runs a console command and collects everything it prints. The server doesn't say
+    /// when a command is done, so the output is taken to be complete once nothing more
+    /// arrived for `quiet`. A command that prints nothing gives an empty string after
+    /// the usual request timeout.
+    pub async fn command_output(
+        &self,
+        command: &str,
+        quiet: Duration,
+    ) -> Result<String, RCONError> {
+        let mut replies = self.request_replies(ClientMessageType::Command(command.to_string()))?;
+        let mut output = String::new();
+        let mut wait = REQUEST_TIMEOUT;
+        loop {
+            match tokio::time::timeout(wait, replies.next()).await {
+                Ok(Some(reply)) => {
+                    if let ServerMessageType::Print { text, .. } = reply.content {
+                        output.push_str(&text);
+                        if !output.ends_with('\n') {
+                            output.push('\n');
+                        }
+                    }
+                    wait = quiet;
+                }
+                Ok(None) => return Err(RCONError::Disconnected),
+                Err(_) => return Ok(output),
+            }
+        }
+    }
+
+    /// Subscribes to everything that happens on the connection from now on.
+    /// Use [`RCONSocket::state`] to find out what already happened.
+    pub fn subscribe(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Watches the login state of the connection, so the UI can react to it changing.
+    pub fn state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.clone()
+    }
+
+    pub fn disconnect(&self) {
+        self.cancel_token.cancel();
+    }
+}
+
+/// The half of the connection that lives in the background task.
+struct Session {
+    next_id: Arc<AtomicUsize>,
+    pending: PendingRequests,
+    password: String,
+    version: protocol::ProtocolVersion,
+    reconnect: bool,
+    connector: Connector,
+    events: broadcast::Sender<ConnectionEvent>,
+    state: watch::Sender<ConnectionState>,
+    cancel_token: CancellationToken,
+}
+
+impl Session {
+    fn emit(&self, event: ConnectionEvent) {
+        // an error here only means nobody is listening right now
+        let _ = self.events.send(event);
+    }
+
+    fn message(&self, content: ClientMessageType) -> ClientMessage {
+        ClientMessage::new(self.next_id.fetch_add(1, Ordering::Relaxed), content)
+    }
+
+    /// Hands a reply to whoever is waiting on it, or gives the message back if nobody is.
+    fn route_reply(&self, message: ServerMessage) -> Option<ServerMessage> {
+        if message.id() == UNSOLICITED_ID {
+            return Some(message);
+        }
+        match self.pending.lock().unwrap().get(&message.id()) {
+            Some(reply_tx) => {
+                let _ = reply_tx.send(message);
+                None
+            }
+            None => Some(message),
+        }
+    }
+
+    /// Drops a message that was sent while the connection was down instead of sending it
+    /// once the connection is back, failing the request it belongs to if it was one.
+    fn discard(&self, message: &str) {
+        let Ok(message) = message.parse::<ClientMessage>() else {
+            return;
+        };
+        if self.pending.lock().unwrap().remove(&message.id()).is_some() {
+            return;
+        }
+        let what = match message.content {
+            ClientMessageType::Command(command) => format!("\"{command}\""),
+            ClientMessageType::Maplist => "The maplist request".to_string(),
+            ClientMessageType::LoginRequest(_) | ClientMessageType::LoginPassword(_) => return,
+        };
+        self.emit(ConnectionEvent::SendError(format!(
+            "{what} was not sent, the connection to the server was lost"
+        )));
+    }
+
+    /// Fails everything that was waiting on a connection that just ended.
+    fn abandon(&self, rx: &mut UnboundedReceiver<String>) {
+        while let Ok(message) = rx.try_recv() {
+            self.discard(&message);
+        }
+        // dropping the reply senders wakes up any outstanding requests
+        self.pending.lock().unwrap().clear();
+    }
+
+    fn close(&self, reason: CloseReason) {
+        // dropping the reply senders wakes up any outstanding requests
+        self.pending.lock().unwrap().clear();
+        if let CloseReason::LoginFailed(reason) = &reason {
+            self.state
+                .send_replace(ConnectionState::LoginFailed(reason.clone()));
+        } else {
+            self.state.send_replace(ConnectionState::Closed);
+        }
+        self.emit(ConnectionEvent::Closed { reason });
+    }
+
+    async fn run(self, req: Request, mut rx: UnboundedReceiver<String>) {
+        let mut attempt = 0;
+        loop {
+            let (reason, was_connected) = self.session(req.clone(), &mut rx, attempt == 0).await;
+            let recoverable = match reason {
+                // a server that was never reachable is most likely a typo rather than a restart
+                CloseReason::ConnectFailed(_) => attempt > 0,
+                CloseReason::ClosedByServer(_) | CloseReason::ConnectionLost(_) => true,
+                CloseReason::LoginFailed(_) | CloseReason::Disconnected => false,
+            };
+            if !self.reconnect || !recoverable {
+                self.close(reason);
+                return;
+            }
+
+            // nothing sent to the connection that ended is replayed on the next one
+            self.abandon(&mut rx);
+            if was_connected {
+                attempt = 0;
+            }
+            attempt += 1;
+            let delay = reconnect_delay(attempt);
+            self.state
+                .send_replace(ConnectionState::Reconnecting { attempt });
+            self.emit(ConnectionEvent::Reconnecting {
+                attempt,
+                delay,
+                reason,
+            });
+            let sleep = tokio::time::sleep(delay);
+            tokio::pin!(sleep);
+            loop {
+                tokio::select! {
+                    _ = self.cancel_token.cancelled() => {
+                        self.close(CloseReason::Disconnected);
+                        return;
+                    }
+                    _ = &mut sleep => break,
+                    Some(msg) = rx.recv() => self.discard(&msg),
+                }
+            }
+        }
+    }
+
+    /// Connects, logs in and relays messages until the connection ends.
+    /// Also returns whether the login succeeded before the connection ended.
+    /// Messages sent before logging in are held back on the first connection,
+    /// and dropped when reconnecting.
+    async fn session(
+        &self,
+        req: Request,
+        rx: &mut UnboundedReceiver<String>,
+        first: bool,
+    ) -> (CloseReason, bool) {
+        self.state.send_replace(ConnectionState::Connecting);
+        self.emit(ConnectionEvent::Connecting);
+        let ws_stream = tokio::select! {
+            _ = self.cancel_token.cancelled() => {
+                return (CloseReason::Disconnected, false);
+            }
+            result = connect_async_tls_with_config(req, None, false, Some(self.connector.clone())) => match result {
+                Ok((ws_stream, _)) => ws_stream,
+                Err(e) => return (CloseReason::ConnectFailed(e.to_string()), false),
+            }
+        };
+        self.emit(ConnectionEvent::Connected);
+
+        let (mut write, mut read) = ws_stream.split();
+
+        let login_request = self.message(ClientMessageType::LoginRequest(self.version.clone()));
+        if let Err(e) = write
+            .send(Message::Text(login_request.serialize().into()))
+            .await
+        {
+            return (CloseReason::ConnectionLost(e.to_string()), false);
+        }
+        self.state.send_replace(ConnectionState::LoggingIn);
+
+        let mut logged_in = false;
+        let reason = loop {
+            // commands are held back in the channel until the first login has succeeded,
+            // while reconnecting they're dropped instead
+            tokio::select! {
+                _ = self.cancel_token.cancelled() => {
+                    let _ = write.send(Message::Close(None)).await;
+                    break CloseReason::Disconnected;
+                }
+                Some(msg) = rx.recv(), if logged_in || !first => {
+                    if !logged_in {
+                        self.discard(&msg);
+                    } else if let Err(e) = write.send(Message::Text(msg.into())).await {
+                        self.emit(ConnectionEvent::SendError(format!("Failed to send message: {}", e)));
+                    }
+                }
+                msg = read.next() => match msg {
+                    Some(Ok(Message::Text(txt))) => match txt.parse::<ServerMessage>() {
+                        Ok(message) => if let Some(message) = self.route_reply(message) { match message.content {
+                            ServerMessageType::LoginResponse(nonce) => {
+                                let answer = self.message(ClientMessageType::LoginPassword(
+                                    protocol::login_digest(&self.password, nonce),
+                                ));
+                                if let Err(e) = write.send(Message::Text(answer.serialize().into())).await {
+                                    self.emit(ConnectionEvent::SendError(format!("Failed to send login password: {}", e)));
+                                }
+                            }
+                            ServerMessageType::LoginSuccess(info) => {
+                                if !first {
+                                    // anything sent while connecting again is still queued
+                                    while let Ok(msg) = rx.try_recv() {
+                                        self.discard(&msg);
+                                    }
+                                }
+                                logged_in = true;
+                                self.state.send_replace(ConnectionState::LoggedIn);
+                                self.emit(ConnectionEvent::Authenticated);
+                                if let Some(info) = info {
+                                    self.emit(ConnectionEvent::ServerInfo(info));
+                                }
+                            }
+                            ServerMessageType::LoginFailure(reason) => {
+                                self.emit(ConnectionEvent::AuthFailed(reason.clone()));
+                                let _ = write.send(Message::Close(None)).await;
+                                break CloseReason::LoginFailed(reason);
+                            }
+                            ServerMessageType::Print { printlevel, text } => {
+                                self.emit(ConnectionEvent::Print { level: printlevel, text });
+                            }
+                            ServerMessageType::Maplist(maps) => self.emit(ConnectionEvent::Maplist(maps)),
+                        }},
+                        Err(e) => self.emit(ConnectionEvent::ProtocolError {
+                            raw: txt.to_string(),
+                            error: e.to_string(),
+                        }),
+                    },
+                    Some(Ok(Message::Close(frame))) => {
+                        break CloseReason::ClosedByServer(
+                            frame
+                                .map(|f| f.reason.to_string())
+                                .filter(|reason| !reason.is_empty()),
+                        );
+                    }
+                    None => break CloseReason::ConnectionLost("stream ended".to_string()),
+                    Some(Err(e)) => break CloseReason::ConnectionLost(e.to_string()),
+                    Some(Ok(_)) => {}
+                }
+            }
+        };
+        (reason, logged_in)
+    }
+}
+
+/// Exponential backoff starting at one second, capped so that a server that is down
+/// for a while gets noticed reasonably quickly once it is back.
+fn reconnect_delay(attempt: u32) -> Duration {
+    let delay = RECONNECT_BASE_DELAY.saturating_mul(1 << attempt.saturating_sub(1).min(16));
+    delay.min(RECONNECT_MAX_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TlsConfig;
+    use crate::mock::{self, MockConfig, MockServer};
+    use rustls::pki_types::PrivatePkcs8KeyDer;
+    use sha2::{Digest, Sha256};
+    use std::path::PathBuf;
+    use tokio::net::TcpListener;
+
+    /// A mock server without any scripted prints, so the only messages are replies
+    async fn echo_server(password: &str) -> MockServer {
+        let config = MockConfig {
+            password: password.to_string(),
+            script: vec![],
+            ..Default::default()
+        };
+        MockServer::start("127.0.0.1:0", config).await.unwrap()
+    }
+
+    /// Same as `echo_server`, but behind TLS with a freshly generated self-signed certificate
+    async fn tls_echo_server(password: &str) -> (u16, rcgen::CertifiedKey<rcgen::KeyPair>) {
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let key = PrivatePkcs8KeyDer::from(certified.signing_key.serialize_der());
+        let config = tokio_rustls::rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(vec![certified.cert.der().clone()], key.into())
+        .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let config = MockConfig {
+            password: password.to_string(),
+            script: vec![],
+            ..Default::default()
+        };
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            // a client that rejects the certificate fails the handshake, which is fine here
+            if let Ok(stream) = acceptor.accept(stream).await {
+                mock::serve_stream(stream, config).await;
+            }
+        });
+        (port, certified)
+    }
+
+    fn local_server(port: u16, password: &str) -> ServerConfig {
+        ServerConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            password: password.to_string(),
+            reconnect: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn connect_invalid_hostname() {
+        let server = ServerConfig {
+            host: "example com".to_string(),
+            ..Default::default()
+        };
+        let s = RCONSocket::connect(&server);
+        assert!(s.is_err())
+    }
+
+    #[tokio::test]
+    async fn login_and_request() {
+        let server = echo_server("hunter2").await;
+        let socket = RCONSocket::connect(&local_server(server.port(), "hunter2")).unwrap();
+        let mut state = socket.state();
+        state
+            .wait_for(|state| *state == ConnectionState::LoggedIn)
+            .await
+            .unwrap();
+
+        let reply = socket
+            .request(ClientMessageType::Command("echo hi".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            reply.content,
+            ServerMessageType::Print {
+                printlevel: PrintLevel::High,
+                text: "hi\n".to_string()
+            }
+        );
+        socket.disconnect();
+    }
+
+    #[tokio::test]
+    async fn login_failure() {
+        let server = echo_server("hunter2").await;
+        let socket = RCONSocket::connect(&local_server(server.port(), "hunter3")).unwrap();
+        let mut state = socket.state();
+        let state = state
+            .wait_for(|state| matches!(state, ConnectionState::LoginFailed(_)))
+            .await
+            .unwrap()
+            .clone();
+        assert_eq!(
+            state,
+            ConnectionState::LoginFailed("Incorrect password".to_string())
+        );
+        assert!(matches!(
+            socket.request(ClientMessageType::Maplist).await,
+            Err(RCONError::Disconnected)
+        ));
+    }
+
+    async fn wait_logged_in(socket: &RCONSocket) {
+        let mut state = socket.state();
+        let state = state
+            .wait_for(|state| matches!(state, ConnectionState::LoggedIn | ConnectionState::Closed))
+            .await
+            .unwrap();
+        assert_eq!(*state, ConnectionState::LoggedIn);
+    }
+
+    #[tokio::test]
+    async fn tls_pinned_fingerprint() {
+        let (port, certified) = tls_echo_server("hunter2").await;
+        let fingerprint = Sha256::digest(certified.cert.der())
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(":");
+        let server = ServerConfig {
+            tls: Some(TlsConfig {
+                ca_file: None,
+                fingerprint: Some(fingerprint),
+            }),
+            ..local_server(port, "hunter2")
+        };
+        let socket = RCONSocket::connect(&server).unwrap();
+        wait_logged_in(&socket).await;
+        socket.disconnect();
+    }
+
+    #[tokio::test]
+    async fn tls_custom_ca() {
+        let (port, certified) = tls_echo_server("hunter2").await;
+        let ca_file = std::env::temp_dir().join(format!("odarcon-test-ca-{}.pem", port));
+        std::fs::write(&ca_file, certified.cert.pem()).unwrap();
+        let server = ServerConfig {
+            host: "localhost".to_string(),
+            tls: Some(TlsConfig {
+                ca_file: Some(ca_file.clone()),
+                fingerprint: None,
+            }),
+            ..local_server(port, "hunter2")
+        };
+        let socket = RCONSocket::connect(&server);
+        std::fs::remove_file(ca_file).unwrap();
+        let socket = socket.unwrap();
+        wait_logged_in(&socket).await;
+        socket.disconnect();
+    }
+
+    #[tokio::test]
+    async fn tls_wrong_fingerprint() {
+        let (port, _) = tls_echo_server("hunter2").await;
+        let server = ServerConfig {
+            tls: Some(TlsConfig {
+                ca_file: None,
+                fingerprint: Some("00".repeat(32)),
+            }),
+            ..local_server(port, "hunter2")
+        };
+        let socket = RCONSocket::connect(&server).unwrap();
+        let mut state = socket.state();
+        let state = state
+            .wait_for(|state| matches!(state, ConnectionState::LoggedIn | ConnectionState::Closed))
+            .await
+            .unwrap();
+        assert_eq!(*state, ConnectionState::Closed);
+    }
+
+    #[test]
+    fn tls_missing_ca_file() {
+        let server = ServerConfig {
+            tls: Some(TlsConfig {
+                ca_file: Some(PathBuf::from("/nonexistent/odarcon/ca.pem")),
+                fingerprint: None,
+            }),
+            ..local_server(11666, "")
+        };
+        assert!(matches!(
+            RCONSocket::connect(&server),
+            Err(RCONError::TlsError(_))
+        ));
+    }
+
+    #[test]
+    fn reconnect_backoff() {
+        assert_eq!(reconnect_delay(1), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(2), Duration::from_secs(2));
+        assert_eq!(reconnect_delay(5), Duration::from_secs(16));
+        assert_eq!(reconnect_delay(6), RECONNECT_MAX_DELAY);
+        assert_eq!(reconnect_delay(u32::MAX), RECONNECT_MAX_DELAY);
+    }
+}
@@ -0,0 +1,40 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! Helpers for putting together the console commands that are sent to a server.
+
+/// Quotes an argument that has spaces or a `;` in it, so the console sees it as one
+/// argument rather than running the rest as another command. The console has no
+/// escapes, so quotes inside are swapped for single quotes.
+pub fn quote(arg: &str) -> String {
+    if arg.is_empty() || arg.contains(char::is_whitespace) || arg.contains(['"', ';']) {
+        format!("\"{}\"", arg.replace('"', "'"))
+    } else {
+        arg.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quote("MAP01"), "MAP01");
+        assert_eq!(quote(""), "\"\"");
+        assert_eq!(quote("two words"), "\"two words\"");
+        assert_eq!(quote("say \"hi\""), "\"say 'hi'\"");
+        assert_eq!(quote("1;quit"), "\"1;quit\"");
+    }
+}
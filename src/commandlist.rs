@@ -0,0 +1,305 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! The console commands and cvars a server has, learned from what its `cmdlist` and
+//! `cvarlist` commands print.
+//!
+//! Servers running different versions have different commands, so the lists are cached
+//! per server and protocol version to have them ready before the server has answered.
+
+use crate::client::{RCONError, RCONSocket};
+use crate::command::quote;
+use crate::config::{Config, ServerConfig};
+use crate::protocol;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long to wait for more output before a list is taken to be complete.
+const LIST_QUIET_TIME: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommandList {
+    pub commands: BTreeSet<String>,
+    /// Cvar names with their values at the time they were listed
+    pub cvars: BTreeMap<String, String>,
+}
+
+/// Something the input can be completed to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub name: String,
+    /// The current value for cvars, `None` for commands
+    pub value: Option<String>,
+}
+
+fn is_name(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Reads the names out of `cmdlist` output, a name per line and a count at the end.
+pub fn parse_cmdlist(output: &str) -> BTreeSet<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| is_name(line))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reads the names and values out of `cvarlist` output. Each line has the cvar's flags,
+/// its name and its value in quotes, like `A  S sv_hostname "My Server"`.
+pub fn parse_cvarlist(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (before, value) = line.split_once('"')?;
+            let name = before.split_whitespace().last()?;
+            let value = value.strip_suffix('"').unwrap_or(value);
+            is_name(name).then(|| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Asks the server for its cvars and their values.
+pub async fn fetch_cvars(socket: &RCONSocket) -> Result<BTreeMap<String, String>, RCONError> {
+    let cvars = socket.command_output("cvarlist", LIST_QUIET_TIME).await?;
+    Ok(parse_cvarlist(&cvars))
+}
+
+/// The part of a cvar's name up to its first underscore, like `sv_` for `sv_gravity`.
+/// Empty for names without one.
+pub fn cvar_group(name: &str) -> &str {
+    name.find('_').map_or("", |i| &name[..=i])
+}
+
+/// The command that sets a cvar.
+pub fn set_command(name: &str, value: &str) -> String {
+    format!("set {name} {}", quote(value))
+}
+
+impl CommandList {
+    /// Asks the server for its commands and cvars.
+    pub async fn fetch(socket: &RCONSocket) -> Result<Self, RCONError> {
+        let commands = socket.command_output("cmdlist", LIST_QUIET_TIME).await?;
+        Ok(Self {
+            commands: parse_cmdlist(&commands),
+            cvars: fetch_cvars(socket).await?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.cvars.is_empty()
+    }
+
+    /// Where the lists of a server are cached, `$XDG_CACHE_HOME/odarcon/commands` on Linux.
+    pub fn cache_path(server: &ServerConfig) -> Option<PathBuf> {
+        let version = protocol::ProtocolVersion::from(server.protoversion);
+        Config::cache_dir().map(|dir| {
+            dir.join("commands").join(format!(
+                "{}-{}.{}.{}.json",
+                server.file_stem(),
+                version.major,
+                version.minor,
+                version.revision
+            ))
+        })
+    }
+
+    /// Loads cached lists, a cache that doesn't exist yet gives empty lists.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).map_err(io::Error::other),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self).map_err(io::Error::other)?)
+    }
+
+    /// Updates the cached value of a cvar after it was changed.
+    pub fn set_cvar(&mut self, name: &str, value: &str) {
+        if let Some(current) = self.cvars.get_mut(name) {
+            *current = value.to_string();
+        }
+    }
+
+    /// Commands and cvars starting with `prefix`, sorted by name. Case insensitive, like
+    /// the console. Cvars only when `cvars_only` is set, for the arguments of `set`.
+    pub fn complete(&self, prefix: &str, cvars_only: bool) -> Vec<Candidate> {
+        let prefix = prefix.to_ascii_lowercase();
+        let matches = |name: &String| name.to_ascii_lowercase().starts_with(&prefix);
+        let commands = self
+            .commands
+            .iter()
+            .filter(|_| !cvars_only)
+            .filter(|name| matches(name))
+            .map(|name| Candidate {
+                name: name.clone(),
+                value: None,
+            });
+        let cvars = self
+            .cvars
+            .iter()
+            .filter(|(name, _)| matches(name))
+            .map(|(name, value)| Candidate {
+                name: name.clone(),
+                value: Some(value.clone()),
+            });
+        let mut candidates: Vec<_> = commands.chain(cvars).collect();
+        candidates.sort_by_key(|candidate| candidate.name.to_ascii_lowercase());
+        candidates
+    }
+}
+
+/// The longest prefix all of the candidates share, ignoring case. It's taken from the
+/// first candidate, so the case of the names is kept.
+pub fn common_prefix(candidates: &[Candidate]) -> &str {
+    let Some((first, rest)) = candidates.split_first() else {
+        return "";
+    };
+    let mut len = first.name.len();
+    for candidate in rest {
+        len = first
+            .name
+            .bytes()
+            .zip(candidate.name.bytes())
+            .take(len)
+            .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count();
+    }
+    &first.name[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lists() {
+        let commands = parse_cmdlist("addmap\nkick\n  map\nsay\n4 commands\n");
+        assert_eq!(
+            commands.into_iter().collect::<Vec<_>>(),
+            vec!["addmap", "kick", "map", "say"]
+        );
+        let cvars = parse_cvarlist(
+            "A  S sv_hostname \"My \"cool\" server\"\n     sv_gravity \"800\"\n   L g_lives \"\"\n3 cvars\n",
+        );
+        assert_eq!(
+            cvars.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("g_lives".to_string(), "".to_string()),
+                ("sv_gravity".to_string(), "800".to_string()),
+                ("sv_hostname".to_string(), "My \"cool\" server".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn completion() {
+        let list = CommandList {
+            commands: BTreeSet::from(["say".to_string(), "sv_restart".to_string()]),
+            cvars: BTreeMap::from([
+                ("sv_gravity".to_string(), "800".to_string()),
+                ("SV_Hostname".to_string(), "test".to_string()),
+            ]),
+        };
+        let names = |candidates: Vec<Candidate>| {
+            candidates
+                .into_iter()
+                .map(|candidate| candidate.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(list.complete("sv_", false)),
+            vec!["sv_gravity", "SV_Hostname", "sv_restart"]
+        );
+        assert_eq!(names(list.complete("SV_G", true)), vec!["sv_gravity"]);
+        assert_eq!(names(list.complete("sv_r", true)), Vec::<String>::new());
+        assert_eq!(list.complete("sa", false)[0].value, None);
+        assert_eq!(
+            list.complete("sv_grav", false)[0].value.as_deref(),
+            Some("800")
+        );
+    }
+
+    #[test]
+    fn common_prefixes() {
+        let candidates = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| Candidate {
+                    name: name.to_string(),
+                    value: None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(common_prefix(&candidates(&[])), "");
+        assert_eq!(common_prefix(&candidates(&["sv_gravity"])), "sv_gravity");
+        assert_eq!(
+            common_prefix(&candidates(&["sv_maxplayers", "SV_MAXCLIENTS"])),
+            "sv_max"
+        );
+    }
+
+    #[test]
+    fn cvar_groups_and_setting() {
+        assert_eq!(cvar_group("sv_gravity"), "sv_");
+        assert_eq!(cvar_group("g_lives"), "g_");
+        assert_eq!(cvar_group("hostname"), "");
+        assert_eq!(
+            set_command("sv_hostname", "My Server"),
+            "set sv_hostname \"My Server\""
+        );
+        assert_eq!(set_command("sv_motd", ""), "set sv_motd \"\"");
+        // a semicolon would run the rest as a second command
+        assert_eq!(
+            set_command("sv_gravity", "0;rcon_password x"),
+            "set sv_gravity \"0;rcon_password x\""
+        );
+        assert_eq!(
+            set_command("sv_gravity", "0;quit"),
+            "set sv_gravity \"0;quit\""
+        );
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "odarcon-test-commands-{}/server.json",
+            std::process::id()
+        ));
+        assert_eq!(CommandList::load(&path).unwrap(), CommandList::default());
+        let mut list = CommandList {
+            commands: BTreeSet::from(["say".to_string()]),
+            cvars: BTreeMap::from([("sv_gravity".to_string(), "800".to_string())]),
+        };
+        list.save(&path).unwrap();
+        list.set_cvar("sv_gravity", "400");
+        list.set_cvar("sv_nope", "1");
+        assert_ne!(CommandList::load(&path).unwrap(), list);
+        list.save(&path).unwrap();
+        assert_eq!(CommandList::load(&path).unwrap(), list);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -12,18 +12,18 @@
  * GNU General Public License for more details.
  */
 
+//! The config file, with the saved servers and the console settings.
+
 use crate::protocol;
 use crate::protocol::PrintLevel;
-use cursive::theme::BaseColor;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::str::FromStr;
 use thiserror::Error;
 
-type CursiveColor = cursive::theme::Color;
-
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("No config directory found")]
@@ -140,6 +140,83 @@ pub struct ServerConfig {
     pub port: u16,
     pub password: String,
     pub protoversion: ProtocolVersion,
+    #[serde(default = "default_reconnect")]
+    pub reconnect: bool,
+    /// Connect with `wss://` instead of `ws://` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    /// Groups the server is in, for broadcasting commands to all of them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Aliases only for this server, they take precedence over the global ones
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, Alias>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// PEM file with extra certificates to trust on top of the system ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_file: Option<PathBuf>,
+    /// SHA-256 fingerprint of the server's certificate, for self-signed certificates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+}
+
+/// A name for one or more commands, see [`crate::alias`].
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Alias {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub steps: Vec<AliasStep>,
+    /// Seconds to wait between each of the commands
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub delay: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum AliasStep {
+    /// A command, which can have `$1`, `$2`... in it for the arguments
+    Command(String),
+    Wait(Wait),
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Wait {
+    /// In seconds
+    pub wait: f64,
+}
+
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+
+fn default_reconnect() -> bool {
+    true
+}
+
+impl ServerConfig {
+    /// A name for files that belong to this server, like its logs. Quick connect servers
+    /// don't have a name so they go by their address instead.
+    pub fn file_stem(&self) -> String {
+        let name = if self.name.trim().is_empty() {
+            format!("{}_{}", self.host, self.port)
+        } else {
+            self.name.trim().to_string()
+        };
+        name.chars()
+            .map(|c| match c {
+                c if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') => c,
+                _ => '_',
+            })
+            .collect::<String>()
+            .trim_start_matches('.')
+            .to_string()
+    }
 }
 
 impl Default for ServerConfig {
@@ -150,31 +227,29 @@ impl Default for ServerConfig {
             port: 11666,
             password: "".to_string(),
             protoversion: ProtocolVersion::Latest,
+            reconnect: default_reconnect(),
+            tls: None,
+            tags: Vec::new(),
+            aliases: BTreeMap::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Color(pub CursiveColor);
-
-impl From<CursiveColor> for Color {
-    fn from(color: CursiveColor) -> Self {
-        Color(color)
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BaseColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
 }
 
-impl From<Color> for CursiveColor {
-    fn from(color: Color) -> Self {
-        color.0
-    }
-}
-
-impl Serialize for Color {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let basecolor_str = |c: BaseColor| match c {
+impl BaseColor {
+    fn name(self) -> &'static str {
+        match self {
             BaseColor::Black => "black",
             BaseColor::Red => "red",
             BaseColor::Green => "green",
@@ -183,33 +258,176 @@ impl Serialize for Color {
             BaseColor::Magenta => "magenta",
             BaseColor::Cyan => "cyan",
             BaseColor::White => "white",
-        };
+        }
+    }
 
-        match self.0 {
-            CursiveColor::TerminalDefault => serializer.serialize_str("default"),
-            CursiveColor::Rgb(r, g, b) => {
-                serializer.serialize_str(&format!("#{:02X}{:02X}{:02X}", r, g, b))
-            }
-            CursiveColor::RgbLowRes(r, g, b) => {
-                serializer.serialize_str(&format!("#{:X}{:X}{:X}", r, g, b))
-            }
-            CursiveColor::Light(basecolor) => {
-                serializer.serialize_str(&format!("light {}", basecolor_str(basecolor)))
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s.to_lowercase().as_str() {
+            "black" => BaseColor::Black,
+            "red" => BaseColor::Red,
+            "green" => BaseColor::Green,
+            "yellow" => BaseColor::Yellow,
+            "blue" => BaseColor::Blue,
+            "magenta" => BaseColor::Magenta,
+            "cyan" => BaseColor::Cyan,
+            "white" => BaseColor::White,
+            _ => return None,
+        })
+    }
+}
+
+/// A terminal color, written the same way as in cursive theme files
+/// (`"red"`, `"light blue"`, `"#FF0000"`, `"default"`...) so that the config doesn't
+/// need cursive to be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    TerminalDefault,
+    Dark(BaseColor),
+    Light(BaseColor),
+    Rgb(u8, u8, u8),
+    /// Each component is in the range 0-5
+    RgbLowRes(u8, u8, u8),
+}
+
+#[derive(Debug, Error)]
+#[error("no such color: {0}")]
+pub struct NoSuchColor(String);
+
+impl FromStr for Color {
+    type Err = NoSuchColor;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "default" || s == "terminal default" {
+            return Ok(Color::TerminalDefault);
+        }
+        if let Some(base) = s.strip_prefix("light ").and_then(BaseColor::parse) {
+            return Ok(Color::Light(base));
+        }
+        if let Some(base) = BaseColor::parse(s.strip_prefix("dark ").unwrap_or(s)) {
+            return Ok(Color::Dark(base));
+        }
+
+        let color = match s.strip_prefix('#').or_else(|| s.strip_prefix("0x")) {
+            Some(hex) => parse_hex(hex),
+            None if s.len() == 6 => parse_hex(s),
+            // without a prefix, three digits are a low resolution color like 050 for green
+            None if s.len() == 3 => {
+                let digits: Vec<u8> = s
+                    .chars()
+                    .filter_map(|c| c.to_digit(6).map(|d| d as u8))
+                    .collect();
+                (digits.len() == 3).then(|| Color::RgbLowRes(digits[0], digits[1], digits[2]))
             }
-            CursiveColor::Dark(basecolor) => serializer.serialize_str(basecolor_str(basecolor)),
+            None => None,
+        };
+        color.ok_or_else(|| NoSuchColor(s.to_string()))
+    }
+}
+
+/// Parses `RRGGBB` or `RGB` hex colors.
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let (len, scale) = match hex.len() {
+        6 => (2, 1),
+        3 => (1, 17),
+        _ => return None,
+    };
+    // unwraps are safe, the digits were checked above
+    let component =
+        |i: usize| u8::from_str_radix(&hex[i * len..(i + 1) * len], 16).unwrap() * scale;
+    Some(Color::Rgb(component(0), component(1), component(2)))
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Color::TerminalDefault => write!(f, "default"),
+            Color::Rgb(r, g, b) => write!(f, "#{:02X}{:02X}{:02X}", r, g, b),
+            Color::RgbLowRes(r, g, b) => write!(f, "{}{}{}", r, g, b),
+            Color::Light(base) => write!(f, "light {}", base.name()),
+            Color::Dark(base) => write!(f, "{}", base.name()),
         }
     }
 }
 
+impl Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
 impl<'de> Deserialize<'de> for Color {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        let color = CursiveColor::from_str(&s)
-            .map_err(|e| serde::de::Error::custom(format!("Invalid color: {e}")))?;
-        Ok(Color(color))
+        Color::from_str(&s).map_err(|e| serde::de::Error::custom(format!("Invalid color: {e}")))
+    }
+}
+
+#[cfg(feature = "tui")]
+mod cursive_color {
+    use super::{BaseColor, Color};
+    use cursive::theme;
+
+    impl From<BaseColor> for theme::BaseColor {
+        fn from(color: BaseColor) -> Self {
+            match color {
+                BaseColor::Black => theme::BaseColor::Black,
+                BaseColor::Red => theme::BaseColor::Red,
+                BaseColor::Green => theme::BaseColor::Green,
+                BaseColor::Yellow => theme::BaseColor::Yellow,
+                BaseColor::Blue => theme::BaseColor::Blue,
+                BaseColor::Magenta => theme::BaseColor::Magenta,
+                BaseColor::Cyan => theme::BaseColor::Cyan,
+                BaseColor::White => theme::BaseColor::White,
+            }
+        }
+    }
+
+    impl From<theme::BaseColor> for BaseColor {
+        fn from(color: theme::BaseColor) -> Self {
+            match color {
+                theme::BaseColor::Black => BaseColor::Black,
+                theme::BaseColor::Red => BaseColor::Red,
+                theme::BaseColor::Green => BaseColor::Green,
+                theme::BaseColor::Yellow => BaseColor::Yellow,
+                theme::BaseColor::Blue => BaseColor::Blue,
+                theme::BaseColor::Magenta => BaseColor::Magenta,
+                theme::BaseColor::Cyan => BaseColor::Cyan,
+                theme::BaseColor::White => BaseColor::White,
+            }
+        }
+    }
+
+    impl From<Color> for theme::Color {
+        fn from(color: Color) -> Self {
+            match color {
+                Color::TerminalDefault => theme::Color::TerminalDefault,
+                Color::Dark(base) => theme::Color::Dark(base.into()),
+                Color::Light(base) => theme::Color::Light(base.into()),
+                Color::Rgb(r, g, b) => theme::Color::Rgb(r, g, b),
+                Color::RgbLowRes(r, g, b) => theme::Color::RgbLowRes(r, g, b),
+            }
+        }
+    }
+
+    impl From<theme::Color> for Color {
+        fn from(color: theme::Color) -> Self {
+            match color {
+                theme::Color::TerminalDefault => Color::TerminalDefault,
+                theme::Color::Dark(base) => Color::Dark(base.into()),
+                theme::Color::Light(base) => Color::Light(base.into()),
+                theme::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+                theme::Color::RgbLowRes(r, g, b) => Color::RgbLowRes(r, g, b),
+            }
+        }
     }
 }
 
@@ -220,6 +438,34 @@ pub struct Config {
     pub servers: Vec<ServerConfig>,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub logcolors: HashMap<PrintLevel, Color>,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, Alias>,
+}
+
+/// Settings for the session logs, see [`crate::sessionlog`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    pub enabled: bool,
+    /// Logs older than this many days are deleted, 0 keeps them forever
+    pub keep_days: u32,
+    /// How many logs to keep for each server, 0 for no limit
+    pub max_files: usize,
+    /// A log is continued in a new file once it reaches this many bytes, 0 for no limit
+    pub max_file_size: u64,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            keep_days: 30,
+            max_files: 100,
+            max_file_size: 10 * 1024 * 1024,
+        }
+    }
 }
 
 impl Config {
@@ -227,6 +473,22 @@ impl Config {
         ProjectDirs::from("net", "odamex", "odarcon").map(|dirs| dirs.config_dir().to_path_buf())
     }
 
+    /// Where logs and history go, `$XDG_STATE_HOME/odarcon` on Linux. Platforms without
+    /// a state directory use the local data directory.
+    pub fn state_dir() -> Option<std::path::PathBuf> {
+        let dirs = ProjectDirs::from("net", "odamex", "odarcon")?;
+        Some(
+            dirs.state_dir()
+                .unwrap_or(dirs.data_local_dir())
+                .to_path_buf(),
+        )
+    }
+
+    /// Where things that can be fetched again go, `$XDG_CACHE_HOME/odarcon` on Linux.
+    pub fn cache_dir() -> Option<std::path::PathBuf> {
+        ProjectDirs::from("net", "odamex", "odarcon").map(|dirs| dirs.cache_dir().to_path_buf())
+    }
+
     pub fn load() -> Result<Self, ConfigError> {
         let config_dir = Self::config_dir().ok_or(ConfigError::NoConfigDir)?;
         let config_path = config_dir.join("config.toml");
@@ -253,12 +515,13 @@ impl Config {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn empty() -> Self {
         Self {
             colorize_logs: false,
             servers: Vec::new(),
             logcolors: HashMap::new(),
+            logging: LoggingConfig::default(),
+            aliases: BTreeMap::new(),
         }
     }
 
@@ -275,6 +538,8 @@ impl Default for Config {
             // TODO: maybe do something different so that if a user doesnt change the colors
             // an old config doesnt leave them with old colors if they change in an update
             logcolors: toml::from_str(include_str!("../res/logcolors.toml")).unwrap(),
+            logging: LoggingConfig::default(),
+            aliases: BTreeMap::new(),
         }
     }
 }
@@ -312,9 +577,32 @@ mod tests {
             port = 10667
             password = "password"
             protoversion = "1.0.0"
+            reconnect = false
+            tags = ["eu", "duel"]
+            [servers.tls]
+            fingerprint = "00:11:22"
+            [servers.aliases.restart]
+            steps = ["map $1"]
+
+            [[servers]]
+            name = "A secure server"
+            host = "rcon.example.com"
+            port = 443
+            password = "password"
+            protoversion = "latest"
+            tls = { ca_file = "/etc/odarcon/ca.pem" }
 
             [logcolors]
             error = "#FF0000"
+
+            [logging]
+            keep_days = 7
+            max_files = 0
+
+            [aliases.nextmap]
+            description = "Warn everyone and change the map"
+            steps = ["say Changing to $1 in 10 seconds", { wait = 10 }, "map $1"]
+            delay = 0.5
         };
         let config = Config {
             colorize_logs: true,
@@ -325,6 +613,10 @@ mod tests {
                     port: 11666,
                     password: "verysecure".to_string(),
                     protoversion: ProtocolVersion::Latest,
+                    reconnect: true,
+                    tls: None,
+                    tags: Vec::new(),
+                    aliases: BTreeMap::new(),
                 },
                 ServerConfig {
                     name: "Another cool server".to_string(),
@@ -336,15 +628,74 @@ mod tests {
                         minor: 0,
                         revision: 0,
                     },
+                    reconnect: false,
+                    tls: Some(TlsConfig {
+                        ca_file: None,
+                        fingerprint: Some("00:11:22".to_string()),
+                    }),
+                    tags: vec!["eu".to_string(), "duel".to_string()],
+                    aliases: BTreeMap::from([(
+                        "restart".to_string(),
+                        Alias {
+                            steps: vec![AliasStep::Command("map $1".to_string())],
+                            ..Default::default()
+                        },
+                    )]),
+                },
+                ServerConfig {
+                    name: "A secure server".to_string(),
+                    host: "rcon.example.com".to_string(),
+                    port: 443,
+                    password: "password".to_string(),
+                    protoversion: ProtocolVersion::Latest,
+                    reconnect: true,
+                    tls: Some(TlsConfig {
+                        ca_file: Some(PathBuf::from("/etc/odarcon/ca.pem")),
+                        fingerprint: None,
+                    }),
+                    tags: Vec::new(),
+                    aliases: BTreeMap::new(),
                 },
             ],
-            logcolors: HashMap::from([(PrintLevel::Error, Color(CursiveColor::Rgb(255, 0, 0)))]),
+            logcolors: HashMap::from([(PrintLevel::Error, Color::Rgb(255, 0, 0))]),
+            logging: LoggingConfig {
+                keep_days: 7,
+                max_files: 0,
+                ..Default::default()
+            },
+            aliases: BTreeMap::from([(
+                "nextmap".to_string(),
+                Alias {
+                    description: "Warn everyone and change the map".to_string(),
+                    steps: vec![
+                        AliasStep::Command("say Changing to $1 in 10 seconds".to_string()),
+                        AliasStep::Wait(Wait { wait: 10.0 }),
+                        AliasStep::Command("map $1".to_string()),
+                    ],
+                    delay: 0.5,
+                },
+            )]),
         };
         let parsed_config =
             toml::from_str::<Config>(&toml_config.to_string()).expect("Failed to parse config");
         assert_eq!(parsed_config, config);
     }
 
+    #[test]
+    fn server_file_stem() {
+        let quick_connect = ServerConfig {
+            host: "10.0.0.1".to_string(),
+            port: 10666,
+            ..Default::default()
+        };
+        assert_eq!(quick_connect.file_stem(), "10.0.0.1_10666");
+        let sneaky = ServerConfig {
+            name: "../etc/passwd".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(sneaky.file_stem(), "_etc_passwd");
+    }
+
     #[test]
     fn parse_config_missing_name() {
         let toml_config = toml::toml! {
@@ -435,13 +786,44 @@ mod tests {
         assert!(parsed_config.is_err());
     }
 
+    #[test]
+    fn parse_colors() {
+        let parse = |s: &str| Color::from_str(s).ok();
+        assert_eq!(parse("default"), Some(Color::TerminalDefault));
+        assert_eq!(parse("red"), Some(Color::Dark(BaseColor::Red)));
+        assert_eq!(parse("dark red"), Some(Color::Dark(BaseColor::Red)));
+        assert_eq!(parse("light blue"), Some(Color::Light(BaseColor::Blue)));
+        assert_eq!(parse("#FF8000"), Some(Color::Rgb(255, 128, 0)));
+        assert_eq!(parse("0xff8000"), Some(Color::Rgb(255, 128, 0)));
+        assert_eq!(parse("ff8000"), Some(Color::Rgb(255, 128, 0)));
+        assert_eq!(parse("#F80"), Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(parse("050"), Some(Color::RgbLowRes(0, 5, 0)));
+        assert_eq!(parse("060"), None);
+        assert_eq!(parse("#GG0000"), None);
+        assert_eq!(parse("1234567"), None);
+        assert_eq!(parse("what"), None);
+    }
+
+    #[test]
+    fn color_round_trip() {
+        for color in [
+            Color::TerminalDefault,
+            Color::Dark(BaseColor::Magenta),
+            Color::Light(BaseColor::Cyan),
+            Color::Rgb(1, 2, 3),
+            Color::RgbLowRes(1, 2, 3),
+        ] {
+            assert_eq!(Color::from_str(&color.to_string()).unwrap(), color);
+        }
+    }
+
+    #[cfg(feature = "tui")]
     #[test]
     fn color_conversion() {
-        let curcolor = CursiveColor::Dark(BaseColor::Red);
-        let mycolor = Color(curcolor.clone());
-        assert_eq!(curcolor, mycolor.clone().into());
-        assert_eq!(Color::from(curcolor), mycolor.clone());
-        assert_eq!(mycolor, curcolor.into());
-        assert_eq!(CursiveColor::from(mycolor), curcolor);
+        type CursiveColor = cursive::theme::Color;
+        let curcolor = CursiveColor::Dark(cursive::theme::BaseColor::Red);
+        let mycolor = Color::Dark(BaseColor::Red);
+        assert_eq!(curcolor, mycolor.into());
+        assert_eq!(Color::from(curcolor), mycolor);
     }
 }
@@ -0,0 +1,318 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use clap::{ArgGroup, Args};
+use odarcon::client::{CloseReason, ConnectionEvent, ConnectionState, RCONSocket};
+use odarcon::config::{Config, ServerConfig};
+use odarcon::protocol::{ClientMessageType, ServerMessageType};
+use odarcon::textcolor;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+// exit code 2 is what clap uses for usage errors
+const EXIT_CONNECTION_FAILED: u8 = 1;
+const EXIT_LOGIN_FAILED: u8 = 3;
+const EXIT_CONFIG_ERROR: u8 = 4;
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("target").required(true).args(["server", "host"])))]
+pub struct ExecArgs {
+    /// Name of a server from the config file
+    #[arg(long, short)]
+    server: Option<String>,
+    /// Hostname of a server that isn't in the config file
+    #[arg(long)]
+    host: Option<String>,
+    /// Port to use with --host
+    #[arg(long, default_value_t = 11666, requires = "host")]
+    port: u16,
+    /// Password to use with --host, prefer the environment variable so it doesn't end up in the process list
+    #[arg(long, env = "ODARCON_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+    /// Seconds to wait for output from the command before exiting
+    #[arg(long, short, default_value = "2", value_parser = parse_seconds)]
+    wait: Duration,
+    /// The command to send, multiple arguments are joined with spaces
+    #[arg(required = true, num_args = 1..)]
+    command: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("targets").required(true).multiple(true).args(["server", "tag", "all"])))]
+pub struct BroadcastArgs {
+    /// Name of a server from the config file, can be given more than once
+    #[arg(long, short)]
+    server: Vec<String>,
+    /// Send to every server with this tag in the config file, can be given more than once
+    #[arg(long, short)]
+    tag: Vec<String>,
+    /// Send to every server in the config file
+    #[arg(long, conflicts_with_all = ["server", "tag"])]
+    all: bool,
+    /// Seconds without output after which a server is taken to be done
+    #[arg(long, short, default_value = "1", value_parser = parse_seconds)]
+    wait: Duration,
+    /// The command to send, multiple arguments are joined with spaces
+    #[arg(required = true, num_args = 1..)]
+    command: Vec<String>,
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let seconds = s.parse::<f64>().map_err(|e| e.to_string())?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string())
+}
+
+/// Finds the server to connect to, either in the config file or from the command line.
+fn resolve_server(args: &ExecArgs) -> Result<ServerConfig, ExitCode> {
+    if let Some(host) = &args.host {
+        return Ok(ServerConfig {
+            name: host.clone(),
+            host: host.clone(),
+            port: args.port,
+            password: args.password.clone().unwrap_or_default(),
+            reconnect: false,
+            ..Default::default()
+        });
+    }
+
+    let name = args.server.as_deref().unwrap_or_default();
+    let config = Config::load().map_err(|e| {
+        eprintln!("Config file could not be loaded: {e}");
+        ExitCode::from(EXIT_CONFIG_ERROR)
+    })?;
+    // prefer an exact match, but don't make people get the capitalization right
+    let server = config
+        .servers
+        .iter()
+        .find(|server| server.name == name)
+        .or_else(|| {
+            config
+                .servers
+                .iter()
+                .find(|server| server.name.eq_ignore_ascii_case(name))
+        });
+    match server {
+        Some(server) => Ok(ServerConfig {
+            reconnect: false,
+            ..server.clone()
+        }),
+        None => {
+            eprintln!("No server named \"{name}\" in the config file");
+            Err(ExitCode::from(EXIT_CONFIG_ERROR))
+        }
+    }
+}
+
+/// Finds out why the connection closed from the events that have piled up.
+fn close_reason(events: &mut broadcast::Receiver<ConnectionEvent>) -> Option<CloseReason> {
+    let mut reason = None;
+    while let Ok(event) = events.try_recv() {
+        if let ConnectionEvent::Closed { reason: r } = event {
+            reason = Some(r);
+        }
+    }
+    reason
+}
+
+async fn disconnect(socket: &RCONSocket) {
+    socket.disconnect();
+    let mut state = socket.state();
+    // give the close frame a chance to go out before the process exits
+    let _ = tokio::time::timeout(
+        DISCONNECT_TIMEOUT,
+        state.wait_for(|state| *state == ConnectionState::Closed),
+    )
+    .await;
+}
+
+/// Logs in, runs a single command and prints its output to stdout.
+pub async fn exec(args: ExecArgs) -> ExitCode {
+    let server = match resolve_server(&args) {
+        Ok(server) => server,
+        Err(code) => return code,
+    };
+
+    let socket = match RCONSocket::connect(&server) {
+        Ok(socket) => socket,
+        Err(e) => {
+            eprintln!("Failed to connect to {}: {e}", server.name);
+            return ExitCode::from(EXIT_CONNECTION_FAILED);
+        }
+    };
+    let mut events = socket.subscribe();
+    let mut state = socket.state();
+    let login = tokio::time::timeout(
+        CONNECT_TIMEOUT,
+        state.wait_for(|state| {
+            !matches!(
+                state,
+                ConnectionState::Connecting | ConnectionState::LoggingIn
+            )
+        }),
+    )
+    .await
+    .map(|state| state.map(|state| state.clone()));
+    match login {
+        Ok(Ok(ConnectionState::LoggedIn)) => {}
+        Ok(Ok(ConnectionState::LoginFailed(reason))) => {
+            eprintln!("Login to {} failed: {reason}", server.name);
+            return ExitCode::from(EXIT_LOGIN_FAILED);
+        }
+        Err(_) => {
+            eprintln!("Timed out connecting to {}", server.name);
+            disconnect(&socket).await;
+            return ExitCode::from(EXIT_CONNECTION_FAILED);
+        }
+        Ok(_) => {
+            match close_reason(&mut events) {
+                Some(CloseReason::ConnectFailed(e)) => {
+                    eprintln!("Failed to connect to {}: {e}", server.name)
+                }
+                _ => eprintln!("Connection to {} was closed", server.name),
+            }
+            return ExitCode::from(EXIT_CONNECTION_FAILED);
+        }
+    }
+
+    let command = ClientMessageType::Command(args.command.join(" "));
+    let mut replies = match socket.request_replies(command) {
+        Ok(replies) => replies,
+        Err(e) => {
+            eprintln!("Failed to send command: {e}");
+            return ExitCode::from(EXIT_CONNECTION_FAILED);
+        }
+    };
+
+    let deadline = tokio::time::sleep(args.wait);
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            reply = replies.next() => match reply {
+                Some(reply) => {
+                    if let ServerMessageType::Print { text, .. } = reply.content {
+                        let text = textcolor::strip(&text);
+                        if text.ends_with('\n') {
+                            print!("{text}");
+                        } else {
+                            println!("{text}");
+                        }
+                    }
+                }
+                None => {
+                    eprintln!("Connection to {} was closed", server.name);
+                    return ExitCode::from(EXIT_CONNECTION_FAILED);
+                }
+            }
+        }
+    }
+
+    disconnect(&socket).await;
+    ExitCode::SUCCESS
+}
+
+/// Picks the servers to broadcast to from the config file.
+fn resolve_servers(args: &BroadcastArgs) -> Result<Vec<ServerConfig>, ExitCode> {
+    let config = Config::load().map_err(|e| {
+        eprintln!("Config file could not be loaded: {e}");
+        ExitCode::from(EXIT_CONFIG_ERROR)
+    })?;
+    if args.all {
+        return Ok(config.servers);
+    }
+    let unknown: Vec<&str> = args
+        .server
+        .iter()
+        .filter(|name| {
+            !config
+                .servers
+                .iter()
+                .any(|server| server.name.eq_ignore_ascii_case(name))
+        })
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        eprintln!(
+            "No server named \"{}\" in the config file",
+            unknown.join("\", \"")
+        );
+        return Err(ExitCode::from(EXIT_CONFIG_ERROR));
+    }
+    let servers: Vec<ServerConfig> =
+        odarcon::broadcast::select(&config.servers, &args.server, &args.tag)
+            .into_iter()
+            .cloned()
+            .collect();
+    if servers.is_empty() {
+        eprintln!(
+            "No servers in the config file have the tag \"{}\"",
+            args.tag.join("\" or \"")
+        );
+        return Err(ExitCode::from(EXIT_CONFIG_ERROR));
+    }
+    Ok(servers)
+}
+
+/// Runs a command on several servers at once and prints what each of them replied,
+/// one after another in the order of the config file.
+pub async fn broadcast(args: BroadcastArgs) -> ExitCode {
+    let servers = match resolve_servers(&args) {
+        Ok(servers) => servers,
+        Err(code) => return code,
+    };
+    let command = args.command.join(" ");
+    let outcomes = odarcon::broadcast::broadcast(&servers, &command, args.wait).await;
+    let mut failed = 0;
+    for (i, outcome) in outcomes.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        match &outcome.output {
+            Ok(output) => {
+                println!("== {} ==", outcome.server);
+                match textcolor::strip(output).as_str() {
+                    "" => println!("(no output)"),
+                    output => print!("{output}"),
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                println!("== {} (failed) ==", outcome.server);
+                println!("{e}");
+            }
+        }
+    }
+    if failed > 0 {
+        eprintln!("{failed} of {} servers failed", outcomes.len());
+        return ExitCode::from(EXIT_CONNECTION_FAILED);
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seconds() {
+        assert_eq!(parse_seconds("2"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_seconds("0.5"), Ok(Duration::from_millis(500)));
+        assert!(parse_seconds("-1").is_err());
+        assert!(parse_seconds("soon").is_err());
+    }
+}
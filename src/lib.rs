@@ -0,0 +1,65 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! Client for the Odamex websocket RCON protocol.
+//!
+//! [`client::RCONSocket`] connects to a server and handles logging in, after which
+//! commands can be sent and everything the server says arrives as
+//! [`client::ConnectionEvent`]s:
+//!
+//! ```no_run
+//! use odarcon::client::{ConnectionEvent, RCONSocket};
+//! use odarcon::config::ServerConfig;
+//! use odarcon::protocol::ClientMessageType;
+//!
+//! # async fn example() -> Result<(), odarcon::client::RCONError> {
+//! let server = ServerConfig {
+//!     host: "127.0.0.1".to_string(),
+//!     password: "hunter2".to_string(),
+//!     ..Default::default()
+//! };
+//! let socket = RCONSocket::connect(&server)?;
+//! let mut events = socket.subscribe();
+//! socket.send(ClientMessageType::Command("say hello".to_string()));
+//! while let Ok(event) = events.recv().await {
+//!     if let ConnectionEvent::Print { text, .. } = event {
+//!         print!("{text}");
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The interactive console is only built with the `tui` feature, so tools that just
+//! want the protocol can use `default-features = false`. The fake server in `mock` is
+//! only built with the `mock` feature, which is meant for the `odarcon-mock` binary
+//! and the tests rather than as part of the API.
+
+pub mod alias;
+pub mod bans;
+pub mod broadcast;
+pub mod client;
+pub mod command;
+pub mod commandlist;
+pub mod config;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod obituary;
+pub mod players;
+pub mod protocol;
+pub mod scoreboard;
+pub mod sessionlog;
+pub mod status;
+pub mod textcolor;
+pub mod tls;
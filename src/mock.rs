@@ -0,0 +1,730 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! A stand-in for an Odamex server, for tests and for trying out the console offline.
+//!
+//! It logs clients in the same way a real server does, echoes commands back,
+//! serves a maplist and sends scripted prints, but there's no game behind it.
+
+use crate::alias::split_args;
+use crate::bans::{self, Ban};
+use crate::players::Player;
+use crate::protocol::{
+    self, ClientMessage, ClientMessageType, LATEST_PROTOCOL_VERSION, MaplistEntry, PrintLevel,
+    ServerInfo, ServerMessage, ServerMessageType, UNSOLICITED_ID,
+};
+use crate::status;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_util::sync::CancellationToken;
+
+/// Typing this into a console connected to the mock server drops the connection.
+pub const DROP_COMMAND: &str = "mock_drop";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptedPrint {
+    pub level: PrintLevel,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockConfig {
+    pub password: String,
+    pub maplist: Vec<MaplistEntry>,
+    /// Prints sent to every client after it logs in
+    pub script: Vec<ScriptedPrint>,
+    /// Without an interval the script is sent once right after logging in,
+    /// with one it loops forever, one print per interval.
+    pub script_interval: Option<Duration>,
+    /// What `cmdlist` prints, the commands the mock actually handles are always in it
+    pub commands: Vec<String>,
+    /// The cvars and their starting values, which can be changed with `set`
+    pub cvars: Vec<(String, String)>,
+    /// Who's listed by `players`, kicking and banning takes them off the list
+    pub players: Vec<Player>,
+    /// What `banlist` starts out with, the indexes are given out again as it changes
+    pub bans: Vec<Ban>,
+}
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        let print = |level, text: &str| ScriptedPrint {
+            level,
+            text: format!("{text}\n"),
+        };
+        let player = |id, name: &str, frags, ping| Player {
+            id,
+            name: name.to_string(),
+            address: format!("127.0.0.{id}:10666"),
+            frags,
+            ping,
+        };
+        let map = |index, map: &str| MaplistEntry {
+            index,
+            map: map.to_string(),
+            wads: vec!["odamex.wad".to_string(), "DOOM2.WAD".to_string()],
+        };
+        Self {
+            password: "".to_string(),
+            maplist: vec![map(0, "MAP01"), map(1, "MAP07"), map(2, "MAP11")],
+            script: vec![
+                print(PrintLevel::Pickup, "Player picked up a shotgun."),
+                print(
+                    PrintLevel::Obituary,
+                    "Player2 was splattered by Player's super shotgun.",
+                ),
+                print(PrintLevel::High, "Player has joined the game."),
+                print(PrintLevel::Chat, "\x1cdPlayer\x1c*: gg"),
+                print(PrintLevel::TeamChat, "Player2: (TEAM) push mid"),
+                print(
+                    PrintLevel::ServerChat,
+                    "[console]: Map changes in 5 minutes",
+                ),
+                print(
+                    PrintLevel::Warning,
+                    "Warning: sv_maxplayers is higher than the slot count",
+                ),
+                print(PrintLevel::Error, "Error: could not find MAP33"),
+            ],
+            script_interval: None,
+            commands: ["addmap", "kick", "map", "say"]
+                .map(str::to_string)
+                .to_vec(),
+            cvars: [
+                ("sv_hostname", "Odamex mock server"),
+                ("sv_maxplayers", "8"),
+                ("sv_gametype", "0"),
+                ("sv_timelimit", "10"),
+                ("sv_fraglimit", "20"),
+                ("sv_gravity", "800"),
+                ("g_lives", "0"),
+            ]
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .to_vec(),
+            players: vec![player(1, "Player", 5, 30), player(2, "Player2", 2, 60)],
+            bans: vec![Ban {
+                index: 1,
+                address: "192.168.0.*".to_string(),
+                expires: None,
+                reason: "Spamming".to_string(),
+            }],
+        }
+    }
+}
+
+/// Commands the mock server understands, on top of the ones in [`MockConfig::commands`].
+const MOCK_COMMANDS: [&str; 17] = [
+    "addban",
+    "ban",
+    "banlist",
+    "cmdlist",
+    "currentmap",
+    "cvarlist",
+    "delban",
+    "delmap",
+    "echo",
+    "forcespec",
+    "get",
+    "kick",
+    "map",
+    "players",
+    "say_to",
+    "set",
+    DROP_COMMAND,
+];
+
+/// State shared by every connection to the same mock server.
+struct Shared {
+    config: MockConfig,
+    maplist: Mutex<Vec<MaplistEntry>>,
+    cvars: Mutex<Vec<(String, String)>>,
+    players: Mutex<Vec<Player>>,
+    bans: Mutex<Vec<Ban>>,
+    /// The map being played, the first one on the maplist until `map` changes it
+    map: Mutex<String>,
+    started: Instant,
+    drop_tx: broadcast::Sender<()>,
+    cancel_token: CancellationToken,
+}
+
+impl Shared {
+    fn new(config: MockConfig) -> Self {
+        Self {
+            maplist: Mutex::new(config.maplist.clone()),
+            cvars: Mutex::new(config.cvars.clone()),
+            players: Mutex::new(config.players.clone()),
+            bans: Mutex::new(config.bans.clone()),
+            map: Mutex::new(
+                config
+                    .maplist
+                    .first()
+                    .map_or("MAP01".to_string(), |entry| entry.map.clone()),
+            ),
+            started: Instant::now(),
+            config,
+            drop_tx: broadcast::channel(1).0,
+            cancel_token: CancellationToken::new(),
+        }
+    }
+}
+
+/// A running mock server, which shuts down when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+}
+
+impl MockServer {
+    /// Starts listening for RCON clients, bind to port 0 to get a free port.
+    pub async fn start(addr: impl ToSocketAddrs, config: MockConfig) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::new(config));
+
+        tokio::spawn({
+            let shared = shared.clone();
+            async move {
+                loop {
+                    tokio::select! {
+                        _ = shared.cancel_token.cancelled() => break,
+                        accepted = listener.accept() => match accepted {
+                            Ok((stream, _)) => {
+                                tokio::spawn(serve(stream, shared.clone()));
+                            }
+                            Err(e) => log::warn!("Mock server could not accept a connection: {e}"),
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self { addr, shared })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// Cuts every open connection without a close frame, like a server that crashed.
+    pub fn drop_connections(&self) {
+        let _ = self.shared.drop_tx.send(());
+    }
+
+    /// Closes every open connection properly and stops accepting new ones.
+    pub fn shutdown(&self) {
+        self.shared.cancel_token.cancel();
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Serves a single client on a stream that's already connected, for example one that
+/// has been wrapped in TLS. The connection is independent of any [`MockServer`].
+pub async fn serve_stream<S>(stream: S, config: MockConfig)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    serve(stream, Arc::new(Shared::new(config))).await;
+}
+
+#[allow(clippy::result_large_err)] // the callback signature is tungstenite's
+fn negotiate_protocol(req: &Request, mut res: Response) -> Result<Response, ErrorResponse> {
+    let wants_rcon = req
+        .headers()
+        .get_all("Sec-WebSocket-Protocol")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|protocol| protocol.trim() == "odamex-rcon");
+    if !wants_rcon {
+        let mut error =
+            ErrorResponse::new(Some("Expected the odamex-rcon subprotocol".to_string()));
+        *error.status_mut() = StatusCode::BAD_REQUEST;
+        return Err(error);
+    }
+    res.headers_mut()
+        .append("Sec-WebSocket-Protocol", "odamex-rcon".parse().unwrap());
+    Ok(res)
+}
+
+fn random_nonce() -> u64 {
+    // not cryptographically strong, but this is a mock
+    RandomState::new().hash_one(std::time::SystemTime::now())
+}
+
+fn high_print(mut text: String) -> ServerMessageType {
+    text.push('\n');
+    ServerMessageType::Print {
+        printlevel: PrintLevel::High,
+        text,
+    }
+}
+
+fn has_cvar(shared: &Shared, name: &str) -> bool {
+    cvar_value(shared, name).is_some()
+}
+
+fn cvar_value(shared: &Shared, name: &str) -> Option<String> {
+    let cvars = shared.cvars.lock().unwrap();
+    cvars
+        .iter()
+        .find(|(cvar, _)| cvar.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.clone())
+}
+
+/// What the mock says it's running when a client logs in, read from its cvars.
+fn server_info(shared: &Shared) -> ServerInfo {
+    let cvar = |name| cvar_value(shared, name).unwrap_or_default();
+    ServerInfo {
+        hostname: cvar("sv_hostname"),
+        map: shared.map.lock().unwrap().clone(),
+        gametype: status::gametype_name(&cvar("sv_gametype"))
+            .unwrap_or("Cooperative")
+            .to_string(),
+        maxplayers: cvar("sv_maxplayers").parse().unwrap_or(0),
+        uptime: shared.started.elapsed().as_secs(),
+    }
+}
+
+/// Prints a cvar when `value` is empty, or sets it. Real servers print nothing when a
+/// cvar is set, this says what changed so there's something to see in the console.
+fn cvar_command(shared: &Shared, name: &str, value: &str) -> ServerMessageType {
+    let mut cvars = shared.cvars.lock().unwrap();
+    let Some((name, current)) = cvars
+        .iter_mut()
+        .find(|(cvar, _)| cvar.eq_ignore_ascii_case(name))
+    else {
+        return high_print(format!("Unknown command {name}"));
+    };
+    let value = value.trim().trim_matches('"');
+    if value.is_empty() {
+        return high_print(format!("\"{name}\" is \"{current}\""));
+    }
+    *current = value.to_string();
+    high_print(format!("\"{name}\" is now \"{current}\""))
+}
+
+/// Takes a player off the list for `kick` and `ban`.
+fn remove_player(shared: &Shared, id: &str) -> Option<Player> {
+    let mut players = shared.players.lock().unwrap();
+    let position = players
+        .iter()
+        .position(|player| id.parse() == Ok(player.id))?;
+    Some(players.remove(position))
+}
+
+/// Reads a ban length like `2 hours`, `None` inside for bans that don't run out.
+fn parse_ban_length(length: &str) -> Option<Option<time::Duration>> {
+    if length.eq_ignore_ascii_case("permanent") {
+        return Some(None);
+    }
+    let (count, unit) = length.split_once(' ')?;
+    let count: i32 = count.parse().ok()?;
+    let unit = match unit.trim().trim_end_matches('s') {
+        "min" | "minute" => time::Duration::MINUTE,
+        "hour" => time::Duration::HOUR,
+        "day" => time::Duration::DAY,
+        "week" => time::Duration::WEEK,
+        _ => return None,
+    };
+    Some(Some(unit * count))
+}
+
+/// Adds a ban for `addban` and `ban`. The arguments after the address are the length
+/// and the reason, quoted like the client quotes them.
+fn add_ban(shared: &Shared, address: &str, args: &[&str]) -> Result<(), String> {
+    let address = address.trim_matches('"');
+    if !bans::valid_mask(address) {
+        return Err(format!("{address} is not an IP address or mask"));
+    }
+    let length = match args.first().map_or("", |arg| arg.trim_matches('"')) {
+        "" => "permanent",
+        length => length,
+    };
+    let Some(length) = parse_ban_length(length) else {
+        return Err(format!("{length} is not a ban length"));
+    };
+    let now = time::OffsetDateTime::now_utc();
+    let now = time::PrimitiveDateTime::new(now.date(), now.time().truncate_to_second());
+    shared.bans.lock().unwrap().push(Ban {
+        index: 0,
+        address: address.to_string(),
+        expires: length.map(|length| now + length),
+        reason: args
+            .get(1)
+            .map_or("", |arg| arg.trim_matches('"'))
+            .to_string(),
+    });
+    Ok(())
+}
+
+/// Finds a player for commands that only print something about them.
+fn player_name(shared: &Shared, args: &str) -> Option<String> {
+    let id = args.split_whitespace().next()?.parse::<u32>().ok()?;
+    let players = shared.players.lock().unwrap();
+    players
+        .iter()
+        .find(|player| player.id == id)
+        .map(|player| player.name.clone())
+}
+
+async fn serve<S>(stream: S, shared: Arc<Shared>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut ws = match tokio_tungstenite::accept_hdr_async(stream, negotiate_protocol).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            log::warn!("Mock server handshake failed: {e}");
+            return;
+        }
+    };
+    let mut drop_rx = shared.drop_tx.subscribe();
+    let mut nonce = None;
+    let mut logged_in = false;
+    let mut script = shared.config.script.iter().cycle();
+    let mut script_timer = shared.config.script_interval.map(tokio::time::interval);
+
+    macro_rules! reply {
+        ($id:expr, $content:expr) => {
+            let message = ServerMessage::new($id, $content);
+            if ws
+                .send(Message::Text(message.serialize().into()))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        };
+    }
+
+    loop {
+        let script_tick = async {
+            match script_timer.as_mut() {
+                Some(timer) if logged_in => timer.tick().await,
+                _ => std::future::pending().await,
+            }
+        };
+        let message = tokio::select! {
+            _ = shared.cancel_token.cancelled() => {
+                let _ = ws.close(None).await;
+                return;
+            }
+            _ = drop_rx.recv() => return,
+            _ = script_tick => {
+                if let Some(print) = script.next() {
+                    reply!(UNSOLICITED_ID, ServerMessageType::Print {
+                        printlevel: print.level.clone(),
+                        text: print.text.clone(),
+                    });
+                }
+                continue;
+            }
+            message = ws.next() => match message {
+                Some(Ok(Message::Text(txt))) => txt,
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return,
+                Some(Ok(_)) => continue,
+            }
+        };
+
+        let message = match message.parse::<ClientMessage>() {
+            Ok(message) => message,
+            Err(e) => {
+                log::warn!("Mock server received an invalid message: {e}");
+                continue;
+            }
+        };
+        let id = message.id();
+        match message.content {
+            ClientMessageType::LoginRequest(version) => {
+                if version.major != LATEST_PROTOCOL_VERSION.major {
+                    reply!(
+                        id,
+                        ServerMessageType::LoginFailure("Unsupported protocol version".to_string())
+                    );
+                    let _ = ws.close(None).await;
+                    return;
+                }
+                let challenge = random_nonce();
+                nonce = Some(challenge);
+                reply!(id, ServerMessageType::LoginResponse(challenge));
+            }
+            ClientMessageType::LoginPassword(answer) => {
+                let expected =
+                    nonce.map(|nonce| protocol::login_digest(&shared.config.password, nonce));
+                if expected.as_ref() != Some(&answer) {
+                    reply!(
+                        id,
+                        ServerMessageType::LoginFailure("Incorrect password".to_string())
+                    );
+                    let _ = ws.close(None).await;
+                    return;
+                }
+                logged_in = true;
+                reply!(
+                    id,
+                    ServerMessageType::LoginSuccess(Some(server_info(&shared)))
+                );
+                if script_timer.is_none() {
+                    for print in &shared.config.script {
+                        reply!(
+                            UNSOLICITED_ID,
+                            ServerMessageType::Print {
+                                printlevel: print.level.clone(),
+                                text: print.text.clone(),
+                            }
+                        );
+                    }
+                }
+            }
+            _ if !logged_in => {
+                reply!(
+                    id,
+                    ServerMessageType::LoginFailure("Not logged in".to_string())
+                );
+                let _ = ws.close(None).await;
+                return;
+            }
+            ClientMessageType::Maplist => {
+                let maplist = shared.maplist.lock().unwrap().clone();
+                reply!(id, ServerMessageType::Maplist(maplist));
+            }
+            ClientMessageType::Command(command) => {
+                let (name, args) = command.split_once(' ').unwrap_or((&command, ""));
+                match name {
+                    DROP_COMMAND => return,
+                    "echo" => {
+                        reply!(
+                            id,
+                            ServerMessageType::Print {
+                                printlevel: PrintLevel::High,
+                                text: format!("{args}\n"),
+                            }
+                        );
+                    }
+                    "delmap" => {
+                        let removed = args.trim().parse::<usize>().ok().and_then(|index| {
+                            let mut maplist = shared.maplist.lock().unwrap();
+                            let position = maplist.iter().position(|entry| entry.index == index)?;
+                            let removed = maplist.remove(position);
+                            for entry in maplist.iter_mut().skip(position) {
+                                entry.index -= 1;
+                            }
+                            Some((removed, maplist.clone()))
+                        });
+                        match removed {
+                            Some((removed, maplist)) => {
+                                reply!(
+                                    id,
+                                    ServerMessageType::Print {
+                                        printlevel: PrintLevel::High,
+                                        text: format!("Removed {} from the maplist\n", removed.map),
+                                    }
+                                );
+                                reply!(UNSOLICITED_ID, ServerMessageType::Maplist(maplist));
+                            }
+                            None => {
+                                reply!(
+                                    id,
+                                    ServerMessageType::Print {
+                                        printlevel: PrintLevel::Warning,
+                                        text: "Map index not found\n".to_string(),
+                                    }
+                                );
+                            }
+                        }
+                    }
+                    "currentmap" => {
+                        let map = shared.map.lock().unwrap().clone();
+                        reply!(id, high_print(map));
+                    }
+                    "map" => {
+                        let map = args.trim().trim_matches('"').to_uppercase();
+                        let text = if map.is_empty() {
+                            "Usage: map <mapname>".to_string()
+                        } else {
+                            *shared.map.lock().unwrap() = map.clone();
+                            format!("Changing map to {map}")
+                        };
+                        reply!(id, high_print(text));
+                    }
+                    "cmdlist" => {
+                        let mut names: Vec<&str> = MOCK_COMMANDS
+                            .into_iter()
+                            .chain(shared.config.commands.iter().map(String::as_str))
+                            .collect();
+                        names.sort_unstable();
+                        names.dedup();
+                        // a print per line, like the real server
+                        for name in &names {
+                            reply!(id, high_print(name.to_string()));
+                        }
+                        reply!(id, high_print(format!("{} commands", names.len())));
+                    }
+                    "cvarlist" => {
+                        let cvars = shared.cvars.lock().unwrap().clone();
+                        for (name, value) in &cvars {
+                            reply!(id, high_print(format!("     {name} \"{value}\"")));
+                        }
+                        reply!(id, high_print(format!("{} cvars", cvars.len())));
+                    }
+                    "get" => {
+                        reply!(id, cvar_command(&shared, args.trim(), ""));
+                    }
+                    "players" => {
+                        let players = shared.players.lock().unwrap().clone();
+                        for player in &players {
+                            reply!(
+                                id,
+                                high_print(format!(
+                                    "({:02}): {} - {} - frags:{} ping:{}",
+                                    player.id,
+                                    player.name,
+                                    player.address,
+                                    player.frags,
+                                    player.ping
+                                ))
+                            );
+                        }
+                    }
+                    "kick" => {
+                        let (player, reason) = args.trim().split_once(' ').unwrap_or((args, ""));
+                        let text = match (remove_player(&shared, player), reason.trim_matches('"'))
+                        {
+                            (None, _) => "Player not found".to_string(),
+                            (Some(player), "") => format!("{} was kicked.", player.name),
+                            (Some(player), reason) => {
+                                format!("{} was kicked: {reason}", player.name)
+                            }
+                        };
+                        reply!(id, high_print(text));
+                    }
+                    "ban" => {
+                        let args = split_args(args);
+                        let text = match remove_player(&shared, args.first().unwrap_or(&"")) {
+                            None => "Player not found".to_string(),
+                            Some(player) => {
+                                let address = player.address.split(':').next().unwrap_or_default();
+                                match add_ban(&shared, address, &args[1..]) {
+                                    Ok(()) => format!("{} was banned.", player.name),
+                                    Err(e) => e,
+                                }
+                            }
+                        };
+                        reply!(id, high_print(text));
+                    }
+                    "addban" => {
+                        let args = split_args(args);
+                        let text = match args.split_first() {
+                            None => "Usage: addban <ip/mask> [length] [reason]".to_string(),
+                            Some((address, rest)) => match add_ban(&shared, address, rest) {
+                                Ok(()) => format!("Added ban on {address}"),
+                                Err(e) => e,
+                            },
+                        };
+                        reply!(id, high_print(text));
+                    }
+                    "delban" => {
+                        let index = args.trim().parse::<usize>().ok();
+                        let removed = index.and_then(|index| {
+                            let mut bans = shared.bans.lock().unwrap();
+                            (1..=bans.len())
+                                .contains(&index)
+                                .then(|| bans.remove(index - 1))
+                        });
+                        let text = match removed {
+                            Some(ban) => format!("Removed ban on {}", ban.address),
+                            None => "Ban not found".to_string(),
+                        };
+                        reply!(id, high_print(text));
+                    }
+                    "banlist" => {
+                        let now = time::OffsetDateTime::now_utc();
+                        let bans = {
+                            let mut bans = shared.bans.lock().unwrap();
+                            bans.retain(|ban| {
+                                ban.expires.is_none_or(|expires| expires.assume_utc() > now)
+                            });
+                            bans.clone()
+                        };
+                        if bans.is_empty() {
+                            reply!(id, high_print("Ban list is empty".to_string()));
+                        }
+                        for (i, ban) in bans.iter().enumerate() {
+                            reply!(
+                                id,
+                                high_print(format!(
+                                    "({:02}): {} - expires: {} - reason: {}",
+                                    i + 1,
+                                    ban.address,
+                                    ban.expires_label(),
+                                    ban.reason
+                                ))
+                            );
+                        }
+                    }
+                    "forcespec" => match player_name(&shared, args) {
+                        Some(name) => {
+                            reply!(id, high_print(format!("{name} was forced to spectate.")));
+                        }
+                        None => {
+                            reply!(id, high_print("Player not found".to_string()));
+                        }
+                    },
+                    "say_to" => {
+                        let message = args
+                            .split_once(' ')
+                            .map_or("", |(_, message)| message.trim().trim_matches('"'));
+                        match player_name(&shared, args) {
+                            Some(name) => {
+                                reply!(id, high_print(format!("[to {name}]: {message}")));
+                            }
+                            None => {
+                                reply!(id, high_print("Player not found".to_string()));
+                            }
+                        }
+                    }
+                    "set" => {
+                        let (name, value) = args.trim().split_once(' ').unwrap_or((args, ""));
+                        reply!(id, cvar_command(&shared, name, value));
+                    }
+                    name if has_cvar(&shared, name) => {
+                        reply!(id, cvar_command(&shared, name, args));
+                    }
+                    _ => {
+                        reply!(id, high_print(command.clone()));
+                    }
+                }
+            }
+        }
+    }
+}
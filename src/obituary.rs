@@ -0,0 +1,147 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! Reading who killed whom out of obituary prints.
+//!
+//! Servers only send the text players see, like `Player2 was splattered by Player's
+//! super shotgun.`, so obituaries are matched against the messages the game prints.
+//! Deaths to monsters and messages this doesn't know are left as plain text.
+
+use crate::textcolor;
+
+/// The obituaries the game prints, `%o` being the player who died and `%k` the one
+/// who killed them, with the weapon or way of dying they stand for.
+const OBITUARIES: [(&str, &str); 26] = [
+    ("%o suicides.", "suicide"),
+    ("%o killed himself.", "suicide"),
+    ("%o killed herself.", "suicide"),
+    ("%o killed itself.", "suicide"),
+    ("%o killed themself.", "suicide"),
+    ("%o died.", "died"),
+    ("%o fell too far.", "falling"),
+    ("%o was squished.", "crushed"),
+    ("%o tried to leave.", "exit"),
+    ("%o can't swim.", "drowning"),
+    ("%o mutated.", "slime"),
+    ("%o melted.", "lava"),
+    ("%o went boom.", "barrel"),
+    ("%o stood in the wrong spot.", "splash"),
+    ("%o should have stood back.", "rocket launcher"),
+    ("%o was telefragged by %k.", "telefrag"),
+    ("%o chewed on %k's fist.", "fist"),
+    ("%o chewed on %k's boomstick.", "shotgun"),
+    ("%o was mowed over by %k's chainsaw.", "chainsaw"),
+    ("%o was tickled by %k's pea shooter.", "pistol"),
+    ("%o was splattered by %k's super shotgun.", "super shotgun"),
+    ("%o was mowed down by %k's chaingun.", "chaingun"),
+    ("%o rode %k's rocket.", "rocket launcher"),
+    ("%o almost dodged %k's rocket.", "rocket launcher"),
+    ("%o was melted by %k's plasma gun.", "plasma gun"),
+    ("%o couldn't hide from %k's BFG.", "BFG"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Obituary {
+    pub victim: String,
+    /// `None` when the victim did it to themselves
+    pub killer: Option<String>,
+    /// The weapon, or how they died when nobody killed them
+    pub method: String,
+    pub suicide: bool,
+}
+
+impl Obituary {
+    /// Reads an obituary from a line the server printed, with or without colors.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = textcolor::strip(line);
+        let line = line.trim();
+        OBITUARIES.iter().find_map(|(pattern, method)| {
+            let (victim, killer) = match_obituary(line, pattern)?;
+            let killer = killer.filter(|killer| *killer != victim);
+            Some(Self {
+                victim: victim.to_string(),
+                suicide: killer.is_none(),
+                killer: killer.map(str::to_string),
+                method: method.to_string(),
+            })
+        })
+    }
+}
+
+/// Matches a line against one of [`OBITUARIES`], giving the victim and the killer.
+fn match_obituary<'a>(line: &'a str, pattern: &str) -> Option<(&'a str, Option<&'a str>)> {
+    let rest = pattern.strip_prefix("%o")?;
+    let (victim, killer) = match rest.split_once("%k") {
+        Some((middle, end)) => {
+            let (victim, killer) = line.strip_suffix(end)?.split_once(middle)?;
+            if killer.is_empty() {
+                return None;
+            }
+            (victim, Some(killer))
+        }
+        None => (line.strip_suffix(rest)?, None),
+    };
+    (!victim.is_empty()).then_some((victim, killer))
+}
+
+/// Reads every obituary in the text of a print.
+pub fn parse_obituaries(text: &str) -> Vec<Obituary> {
+    text.lines().filter_map(Obituary::parse).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obituary(victim: &str, killer: Option<&str>, method: &str) -> Obituary {
+        Obituary {
+            victim: victim.to_string(),
+            killer: killer.map(str::to_string),
+            method: method.to_string(),
+            suicide: killer.is_none(),
+        }
+    }
+
+    #[test]
+    fn kills_and_suicides() {
+        assert_eq!(
+            Obituary::parse("Player2 was splattered by Player's super shotgun.\n"),
+            Some(obituary("Player2", Some("Player"), "super shotgun"))
+        );
+        assert_eq!(
+            Obituary::parse("\x1cdBig Gun\x1c- rode \x1cfJo's\x1c-'s rocket."),
+            Some(obituary("Big Gun", Some("Jo's"), "rocket launcher"))
+        );
+        assert_eq!(
+            Obituary::parse("Player fell too far."),
+            Some(obituary("Player", None, "falling"))
+        );
+        // splash damage from their own rocket
+        assert_eq!(
+            Obituary::parse("Player almost dodged Player's rocket."),
+            Some(obituary("Player", None, "rocket launcher"))
+        );
+    }
+
+    #[test]
+    fn other_lines() {
+        assert_eq!(Obituary::parse("Player was bit by a demon."), None);
+        assert_eq!(Obituary::parse(" fell too far."), None);
+        assert_eq!(Obituary::parse("Player was telefragged by ."), None);
+        assert_eq!(
+            parse_obituaries("Player has joined the game.\nPlayer died.\n"),
+            vec![obituary("Player", None, "died")]
+        );
+    }
+}
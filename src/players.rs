@@ -0,0 +1,179 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! The players on a server and the commands for dealing with them.
+//!
+//! There's no protocol message for the player list, so it's read from what the
+//! `players` command prints, one line per player:
+//!
+//! ```text
+//! (01): Player - 127.0.0.1:10666 - frags:5 ping:30
+//! ```
+
+use crate::bans;
+use crate::client::{RCONError, RCONSocket};
+use crate::command::quote;
+use crate::textcolor;
+use std::time::Duration;
+
+/// How long `players` has to be quiet before the list is taken to be complete.
+const LIST_QUIET_TIME: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Player {
+    /// The number the server knows the player by, which commands take
+    pub id: u32,
+    pub name: String,
+    pub address: String,
+    pub frags: i32,
+    pub ping: u32,
+}
+
+impl Player {
+    /// Parses a line of the player listing, `None` for lines that aren't about a player.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let (id, rest) = line.strip_prefix('(')?.split_once("):")?;
+        let id = id.trim().parse().ok()?;
+        // names can contain " - " too, so the address and stats are taken from the end
+        let (rest, stats) = rest.rsplit_once(" - ")?;
+        let (name, address) = rest.rsplit_once(" - ")?;
+        let mut frags = 0;
+        let mut ping = 0;
+        for stat in stats.split_whitespace() {
+            match stat.split_once(':') {
+                Some(("frags", value)) => frags = value.parse().ok()?,
+                Some(("ping", value)) => ping = value.parse().ok()?,
+                _ => {}
+            }
+        }
+        Some(Self {
+            id,
+            name: name.trim().to_string(),
+            address: address.trim().to_string(),
+            frags,
+            ping,
+        })
+    }
+
+    /// The name without color codes.
+    pub fn plain_name(&self) -> String {
+        textcolor::strip(&self.name)
+    }
+}
+
+/// Reads every player from the output of `players`.
+pub fn parse_players(output: &str) -> Vec<Player> {
+    output.lines().filter_map(Player::parse).collect()
+}
+
+/// Asks the server who's playing.
+pub async fn fetch(socket: &RCONSocket) -> Result<Vec<Player>, RCONError> {
+    let output = socket.command_output("players", LIST_QUIET_TIME).await?;
+    Ok(parse_players(&output))
+}
+
+/// Something that can be done to a player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerAction {
+    Kick {
+        reason: String,
+    },
+    /// An empty length bans for good
+    Ban {
+        length: String,
+        reason: String,
+    },
+    ForceSpectate,
+    Message(String),
+}
+
+impl PlayerAction {
+    /// The console command that does this to the player with the id.
+    pub fn command(&self, id: u32) -> String {
+        match self {
+            Self::Kick { reason } if reason.is_empty() => format!("kick {id}"),
+            Self::Kick { reason } => format!("kick {id} {}", quote(reason)),
+            Self::Ban { length, reason } => {
+                format!("ban {id} {}", bans::length_and_reason(length, reason))
+            }
+            Self::ForceSpectate => format!("forcespec {id}"),
+            Self::Message(message) => format!("say_to {id} {}", quote(message)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_listing() {
+        let output = "(01): Player - 127.0.0.1:10666 - frags:5 ping:30\n\
+                      not a player\n\
+                      (12): \\cdA - B - 10.0.0.2:10666 - frags:-1 ping:120\n";
+        assert_eq!(
+            parse_players(output),
+            vec![
+                Player {
+                    id: 1,
+                    name: "Player".to_string(),
+                    address: "127.0.0.1:10666".to_string(),
+                    frags: 5,
+                    ping: 30,
+                },
+                Player {
+                    id: 12,
+                    name: "\\cdA - B".to_string(),
+                    address: "10.0.0.2:10666".to_string(),
+                    frags: -1,
+                    ping: 120,
+                },
+            ]
+        );
+        assert_eq!(parse_players(output)[1].plain_name(), "A - B");
+        assert_eq!(Player::parse("(xx): Player - 1.2.3.4 - frags:1"), None);
+    }
+
+    #[test]
+    fn action_commands() {
+        let kick = PlayerAction::Kick {
+            reason: String::new(),
+        };
+        assert_eq!(kick.command(3), "kick 3");
+        let kick = PlayerAction::Kick {
+            reason: "say \"please\" next time".to_string(),
+        };
+        assert_eq!(kick.command(3), "kick 3 \"say 'please' next time\"");
+        let ban = PlayerAction::Ban {
+            length: " ".to_string(),
+            reason: "cheating".to_string(),
+        };
+        assert_eq!(ban.command(7), "ban 7 permanent cheating");
+        let ban = PlayerAction::Ban {
+            length: "2 hours".to_string(),
+            reason: String::new(),
+        };
+        assert_eq!(ban.command(7), "ban 7 \"2 hours\"");
+        assert_eq!(PlayerAction::ForceSpectate.command(2), "forcespec 2");
+        assert_eq!(
+            PlayerAction::Message("hi there".to_string()).command(2),
+            "say_to 2 \"hi there\""
+        );
+        assert_eq!(
+            PlayerAction::Message("gg\";quit".to_string()).command(2),
+            "say_to 2 \"gg';quit\""
+        );
+    }
+}
@@ -12,10 +12,13 @@
  * GNU General Public License for more details.
  */
 
+//! The JSON messages that make up the Odamex RCON protocol.
+
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::Sha256;
 use std::fmt::Display;
 use std::str::FromStr;
-use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -45,12 +48,37 @@ pub enum PrintLevel {
 pub enum ServerMessageType {
     LoginResponse(u64),
     LoginFailure(String),
-    LoginSuccess, // TODO: should this include something like sv_hostname? for printing "Successfully connect to <hostname>"
+    /// Newer servers say what they're running, older ones send `null`
+    LoginSuccess(Option<ServerInfo>),
     Print {
         printlevel: PrintLevel,
         text: String,
     },
-    Maplist,
+    Maplist(Vec<MaplistEntry>),
+}
+
+/// What a server is running, sent along with a successful login.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerInfo {
+    pub hostname: String,
+    /// Lump name of the map being played
+    pub map: String,
+    /// The game mode, like `Deathmatch` or `Capture the Flag`
+    pub gametype: String,
+    pub maxplayers: u32,
+    /// Seconds since the server was started
+    pub uptime: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MaplistEntry {
+    /// Position in the server's maplist, as used by commands like `gotomap`
+    pub index: usize,
+    /// Map lump name, like `MAP01` or `E1M1`
+    pub map: String,
+    pub wads: Vec<String>,
 }
 
 pub const LATEST_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion {
@@ -138,13 +166,18 @@ pub struct Message<T: MessageContent> {
     id: usize,
 }
 
+/// Id used by the server for messages that aren't a reply to anything the client sent.
+pub const UNSOLICITED_ID: usize = 0;
+
 impl<T: MessageContent> Message<T> {
-    pub fn new(content: T) -> Self {
-        static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
-        Self {
-            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
-            content,
-        }
+    /// Replies from the server carry the id of the client message they answer,
+    /// so ids should be unique for the lifetime of a connection.
+    pub fn new(id: usize, content: T) -> Self {
+        Self { id, content }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
     }
 
     pub fn serialize(&self) -> String {
@@ -169,6 +202,22 @@ impl<T: MessageContent> Display for Message<T> {
 pub type ServerMessage = Message<ServerMessageType>;
 pub type ClientMessage = Message<ClientMessageType>;
 
+/// Computes the answer to a `LoginResponse` challenge.
+///
+/// The server sends a random nonce, and the client proves that it knows the password
+/// by replying with the hex encoded HMAC-SHA256 of the nonce (as big endian bytes)
+/// keyed with the password, so the password itself never goes over the wire.
+pub fn login_digest(password: &str, nonce: u64) -> String {
+    // unwrap is safe, HMAC accepts keys of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(password.as_bytes()).unwrap();
+    mac.update(&nonce.to_be_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,7 +274,7 @@ mod tests {
         assert_eq!(
             parsed,
             ServerMessage {
-                content: ServerMessageType::LoginSuccess,
+                content: ServerMessageType::LoginSuccess(None),
                 id: 2,
             }
         );
@@ -299,12 +348,38 @@ mod tests {
         assert_eq!(
             parsed,
             ServerMessage {
-                content: ServerMessageType::LoginSuccess,
+                content: ServerMessageType::LoginSuccess(None),
                 id: 2,
             }
         );
     }
 
+    #[test]
+    fn deserialize_login_success_with_info() {
+        let json = json!({
+            "type": "login_success",
+            "id": 2,
+            "content": {
+                "hostname": "Duel",
+                "map": "MAP07",
+                "gametype": "Deathmatch",
+                "maxplayers": 2,
+                "uptime": 3600
+            }
+        });
+        let parsed = serde_json::from_value::<ServerMessage>(json).unwrap();
+        assert_eq!(
+            parsed.content,
+            ServerMessageType::LoginSuccess(Some(ServerInfo {
+                hostname: "Duel".to_string(),
+                map: "MAP07".to_string(),
+                gametype: "Deathmatch".to_string(),
+                maxplayers: 2,
+                uptime: 3600,
+            }))
+        );
+    }
+
     #[test]
     fn deserialize_login_failure_string() {
         let json = r#"{
@@ -322,6 +397,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deserialize_maplist() {
+        let json = json!({
+            "type": "maplist",
+            "id": 3,
+            "content": [
+                { "index": 0, "map": "MAP01", "wads": ["odamex.wad", "DOOM2.WAD"] },
+                { "index": 1, "map": "MAP07", "wads": [] }
+            ]
+        });
+        let parsed = serde_json::from_value::<ServerMessage>(json).unwrap();
+        assert_eq!(
+            parsed,
+            ServerMessage {
+                content: ServerMessageType::Maplist(vec![
+                    MaplistEntry {
+                        index: 0,
+                        map: "MAP01".to_string(),
+                        wads: vec!["odamex.wad".to_string(), "DOOM2.WAD".to_string()],
+                    },
+                    MaplistEntry {
+                        index: 1,
+                        map: "MAP07".to_string(),
+                        wads: vec![],
+                    },
+                ]),
+                id: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_maplist_request() {
+        let message = ClientMessage {
+            content: ClientMessageType::Maplist,
+            id: 7,
+        };
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "type": "maplist",
+                "id": 7
+            })
+        );
+    }
+
     #[test]
     fn serialize_command() {
         let message = ClientMessage {
@@ -360,6 +482,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn login_digest_known_answer() {
+        assert_eq!(
+            login_digest("password", 2345234),
+            "1c4fb312c20d5027a191bd95077b82bd1c64783b59f0086f09df8f25d2a0b14c"
+        );
+        assert_ne!(
+            login_digest("password", 2345234),
+            login_digest("password", 2345235)
+        );
+    }
+
     #[test]
     fn serialize_login_password() {
         let message = ClientMessage {
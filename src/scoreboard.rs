@@ -0,0 +1,211 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! Frags and deaths counted from the obituaries seen during a session.
+//!
+//! Frags are counted the way the game does: one for every kill, minus one for every
+//! suicide. Players who were connected before the session started will have different
+//! totals on the server.
+
+use crate::obituary::Obituary;
+use serde::Serialize;
+use std::path::Path;
+use std::{fs, io};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Tally {
+    pub name: String,
+    pub frags: i32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub suicides: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsFormat {
+    Csv,
+    Json,
+}
+
+impl StatsFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Scoreboard {
+    tallies: Vec<Tally>,
+    obituaries: usize,
+}
+
+impl Scoreboard {
+    fn tally(&mut self, name: &str) -> &mut Tally {
+        let index = match self.tallies.iter().position(|tally| tally.name == name) {
+            Some(index) => index,
+            None => {
+                self.tallies.push(Tally {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                self.tallies.len() - 1
+            }
+        };
+        &mut self.tallies[index]
+    }
+
+    pub fn record(&mut self, obituary: &Obituary) {
+        self.obituaries += 1;
+        let victim = self.tally(&obituary.victim);
+        victim.deaths += 1;
+        if obituary.suicide {
+            victim.suicides += 1;
+            victim.frags -= 1;
+        }
+        if let Some(killer) = &obituary.killer {
+            let killer = self.tally(killer);
+            killer.kills += 1;
+            killer.frags += 1;
+        }
+    }
+
+    /// How many obituaries were counted.
+    pub fn obituaries(&self) -> usize {
+        self.obituaries
+    }
+
+    /// The players from most frags to least, fewer deaths first when that's even.
+    pub fn standings(&self) -> Vec<Tally> {
+        let mut standings = self.tallies.clone();
+        standings.sort_by(|a, b| {
+            b.frags
+                .cmp(&a.frags)
+                .then(a.deaths.cmp(&b.deaths))
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+        standings
+    }
+
+    /// The standings with a header row, names are quoted when they need to be.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("name,frags,kills,deaths,suicides\n");
+        for tally in self.standings() {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                csv_field(&tally.name),
+                tally.frags,
+                tally.kills,
+                tally.deaths,
+                tally.suicides
+            ));
+        }
+        csv
+    }
+
+    /// The standings as an array of objects with the same fields as the CSV.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.standings()).expect("tallies serialize")
+    }
+
+    pub fn export(&self, path: &Path, format: StatsFormat) -> io::Result<()> {
+        let contents = match format {
+            StatsFormat::Csv => self.to_csv(),
+            StatsFormat::Json => self.to_json(),
+        };
+        fs::write(path, contents)
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obituary::parse_obituaries;
+
+    fn scoreboard() -> Scoreboard {
+        let mut scoreboard = Scoreboard::default();
+        let obituaries = parse_obituaries(
+            "Player2 was splattered by Player's super shotgun.\n\
+             Player2 rode Player's rocket.\n\
+             Player fell too far.\n\
+             Player rode \"Big, Gun\"'s rocket.\n",
+        );
+        for obituary in &obituaries {
+            scoreboard.record(obituary);
+        }
+        scoreboard
+    }
+
+    #[test]
+    fn tallies() {
+        let scoreboard = scoreboard();
+        assert_eq!(scoreboard.obituaries(), 4);
+        let standings = scoreboard.standings();
+        assert_eq!(
+            standings[0],
+            Tally {
+                name: "\"Big, Gun\"".to_string(),
+                frags: 1,
+                kills: 1,
+                deaths: 0,
+                suicides: 0,
+            }
+        );
+        assert_eq!(
+            standings[1],
+            Tally {
+                name: "Player".to_string(),
+                frags: 1,
+                kills: 2,
+                deaths: 2,
+                suicides: 1,
+            }
+        );
+        assert_eq!(standings[2].name, "Player2");
+        assert_eq!(standings[2].frags, 0);
+    }
+
+    #[test]
+    fn exports() {
+        let scoreboard = scoreboard();
+        assert_eq!(
+            scoreboard.to_csv(),
+            "name,frags,kills,deaths,suicides\n\
+             \"\"\"Big, Gun\"\"\",1,1,0,0\n\
+             Player,1,2,2,1\n\
+             Player2,0,0,2,0\n"
+        );
+        let json: serde_json::Value = serde_json::from_str(&scoreboard.to_json()).unwrap();
+        assert_eq!(
+            json[1],
+            serde_json::json!({
+                "name": "Player",
+                "frags": 1,
+                "kills": 2,
+                "deaths": 2,
+                "suicides": 1
+            })
+        );
+    }
+}
@@ -0,0 +1,291 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! Logs of RCON sessions, one directory per server with a file per session.
+//!
+//! Every line starts with a UTC timestamp and a tag saying what it is:
+//!
+//! ```text
+//! 2026-10-16T18:04:12Z [status] Logged in
+//! 2026-10-16T18:04:15Z [command] sv_maxplayers 8
+//! 2026-10-16T18:04:15Z [print:high] "sv_maxplayers" is "8"
+//! ```
+
+use crate::client::ConnectionEvent;
+use crate::config::{Config, LoggingConfig, ServerConfig};
+use crate::protocol::PrintLevel;
+use crate::textcolor;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use time::OffsetDateTime;
+use time::macros::format_description;
+
+pub struct SessionLog {
+    dir: PathBuf,
+    settings: LoggingConfig,
+    /// When the session started, the files of a session all start with it
+    started: String,
+    file: BufWriter<File>,
+    path: PathBuf,
+    written: u64,
+    part: u32,
+}
+
+impl SessionLog {
+    /// Where the logs go, `$XDG_STATE_HOME/odarcon/logs` on Linux.
+    pub fn log_dir() -> Option<PathBuf> {
+        Config::state_dir().map(|dir| dir.join("logs"))
+    }
+
+    /// Starts a new log for a session with the server, and deletes old logs of that
+    /// server that are past the retention limits.
+    pub fn open(server: &ServerConfig, settings: &LoggingConfig) -> io::Result<Self> {
+        let dir = Self::log_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No state directory found"))?;
+        Self::open_in(&dir, server, settings)
+    }
+
+    /// Same as [`SessionLog::open`], with the logs under `dir` instead of the usual place.
+    pub fn open_in(
+        dir: &Path,
+        server: &ServerConfig,
+        settings: &LoggingConfig,
+    ) -> io::Result<Self> {
+        let dir = dir.join(server.file_stem());
+        std::fs::create_dir_all(&dir)?;
+        let started = OffsetDateTime::now_utc()
+            .format(format_description!(
+                "[year]-[month]-[day]_[hour]-[minute]-[second]"
+            ))
+            .map_err(io::Error::other)?;
+        let (file, path) = create_file(&dir, &started, 0)?;
+        let log = Self {
+            dir,
+            settings: settings.clone(),
+            started,
+            file,
+            path,
+            written: 0,
+            part: 0,
+        };
+        if let Err(e) = prune(&log.dir, &log.path, &log.settings) {
+            log::warn!("Could not delete old session logs: {e}");
+        }
+        Ok(log)
+    }
+
+    /// The file currently being written to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn command(&mut self, command: &str) -> io::Result<()> {
+        self.write("command", command)
+    }
+
+    pub fn print(&mut self, level: &PrintLevel, text: &str) -> io::Result<()> {
+        self.write(&format!("print:{}", level_name(level)), text)
+    }
+
+    /// Records a message from odarcon itself rather than the server.
+    pub fn status(&mut self, text: &str) -> io::Result<()> {
+        self.write("status", text)
+    }
+
+    pub fn event(&mut self, event: &ConnectionEvent) -> io::Result<()> {
+        match event {
+            ConnectionEvent::Connecting => self.status("Connecting"),
+            ConnectionEvent::Connected => self.status("Connected"),
+            ConnectionEvent::Authenticated => self.status("Logged in"),
+            ConnectionEvent::AuthFailed(reason) => self.status(&format!("Login failed: {reason}")),
+            ConnectionEvent::Print { level, text } => self.print(level, text),
+            ConnectionEvent::ServerInfo(info) => self.status(&format!(
+                "Server is {} playing {} on {}",
+                info.hostname, info.gametype, info.map
+            )),
+            ConnectionEvent::Maplist(maps) => {
+                self.status(&format!("Received maplist ({} maps)", maps.len()))
+            }
+            ConnectionEvent::ProtocolError { raw, error } => {
+                self.status(&format!("Received invalid message: {error}: {raw}"))
+            }
+            ConnectionEvent::SendError(error) => self.status(&format!("Send failed: {error}")),
+            ConnectionEvent::Reconnecting {
+                attempt,
+                delay,
+                reason,
+            } => self.status(&format!(
+                "{reason}, reconnecting in {}s (attempt {attempt})",
+                delay.as_secs()
+            )),
+            ConnectionEvent::Closed { reason } => self.status(&reason.to_string()),
+        }
+    }
+
+    fn write(&mut self, tag: &str, text: &str) -> io::Result<()> {
+        if self.settings.max_file_size > 0 && self.written >= self.settings.max_file_size {
+            self.rotate()?;
+        }
+        let timestamp = OffsetDateTime::now_utc()
+            .format(format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second]Z"
+            ))
+            .map_err(io::Error::other)?;
+        let text = textcolor::strip(text);
+        for line in text.trim_end_matches('\n').lines() {
+            let line = format!("{timestamp} [{tag}] {line}\n");
+            self.file.write_all(line.as_bytes())?;
+            self.written += line.len() as u64;
+        }
+        // flushed every time so nothing is lost if odarcon is killed
+        self.file.flush()
+    }
+
+    /// Continues the session in a new file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.part += 1;
+        let (file, path) = create_file(&self.dir, &self.started, self.part)?;
+        self.file = file;
+        self.path = path;
+        self.written = 0;
+        if let Err(e) = prune(&self.dir, &self.path, &self.settings) {
+            log::warn!("Could not delete old session logs: {e}");
+        }
+        Ok(())
+    }
+}
+
+fn create_file(dir: &Path, started: &str, part: u32) -> io::Result<(BufWriter<File>, PathBuf)> {
+    let name = match part {
+        0 => format!("{started}.log"),
+        part => format!("{started}_{part}.log"),
+    };
+    let path = dir.join(name);
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    Ok((BufWriter::new(file), path))
+}
+
+fn level_name(level: &PrintLevel) -> &'static str {
+    match level {
+        PrintLevel::Pickup => "pickup",
+        PrintLevel::Obituary => "obituary",
+        PrintLevel::High => "high",
+        PrintLevel::Chat => "chat",
+        PrintLevel::TeamChat => "teamchat",
+        PrintLevel::ServerChat => "serverchat",
+        PrintLevel::Warning => "warning",
+        PrintLevel::Error => "error",
+    }
+}
+
+/// Deletes logs in `dir` that are too old, or too many, apart from `current`.
+fn prune(dir: &Path, current: &Path, settings: &LoggingConfig) -> io::Result<()> {
+    let mut logs = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path == current || path.extension().is_none_or(|ext| ext != "log") {
+            continue;
+        }
+        let modified = entry.metadata()?.modified()?;
+        logs.push((modified, path));
+    }
+    // newest first, the names sort by time too for files written in the same instant
+    logs.sort_by(|a, b| b.cmp(a));
+
+    let max_age = Duration::from_secs(u64::from(settings.keep_days) * 24 * 60 * 60);
+    let now = SystemTime::now();
+    for (i, (modified, path)) in logs.iter().enumerate() {
+        let too_old =
+            settings.keep_days > 0 && now.duration_since(*modified).unwrap_or_default() > max_age;
+        // the current file counts towards the limit
+        let too_many = settings.max_files > 0 && i + 1 >= settings.max_files;
+        if too_old || too_many {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("odarcon-test-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn server(name: &str) -> ServerConfig {
+        ServerConfig {
+            name: name.to_string(),
+            host: "127.0.0.1".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn writes_tagged_lines() {
+        let dir = temp_dir("sessionlog-lines");
+        let mut log =
+            SessionLog::open_in(&dir, &server("Test Server"), &LoggingConfig::default()).unwrap();
+        assert!(log.path().starts_with(dir.join("Test_Server")));
+        log.event(&ConnectionEvent::Authenticated).unwrap();
+        log.command("sv_maxplayers 8").unwrap();
+        log.print(&PrintLevel::Chat, "\x1cdPlayer\x1c-: hi\nsecond line\n")
+            .unwrap();
+        let content = std::fs::read_to_string(log.path()).unwrap();
+        let tails: Vec<_> = content
+            .lines()
+            .map(|line| line.split_once(' ').unwrap().1)
+            .collect();
+        assert_eq!(
+            tails,
+            vec![
+                "[status] Logged in",
+                "[command] sv_maxplayers 8",
+                "[print:chat] Player: hi",
+                "[print:chat] second line",
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotates_and_prunes() {
+        let dir = temp_dir("sessionlog-rotate");
+        let settings = LoggingConfig {
+            max_files: 2,
+            max_file_size: 10,
+            ..Default::default()
+        };
+        let mut log = SessionLog::open_in(&dir, &server("rotate"), &settings).unwrap();
+        let first = log.path().to_path_buf();
+        for i in 0..3 {
+            log.command(&format!("command {i}")).unwrap();
+        }
+        assert!(log.path().to_string_lossy().ends_with("_2.log"));
+        let mut files: Vec<_> = std::fs::read_dir(dir.join("rotate"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        assert_eq!(files.len(), 2);
+        assert!(!files.contains(&first));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
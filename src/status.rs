@@ -0,0 +1,198 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! A one line summary of how a server is doing.
+//!
+//! Newer servers describe themselves when a client logs in, see
+//! [`ServerInfo`](crate::protocol::ServerInfo). For older ones the same things are
+//! read from cvars like `sv_hostname` and `sv_gametype` instead, which leaves the
+//! uptime unknown. The map is asked for with `currentmap` every time the server is
+//! pinged, since it changes during a session.
+
+use crate::client::{RCONError, RCONSocket};
+use crate::protocol::{ClientMessageType, ServerMessageType};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// The name of a game mode from the value of `sv_gametype`.
+pub fn gametype_name(value: &str) -> Option<&'static str> {
+    match value.trim() {
+        "0" => Some("Cooperative"),
+        "1" => Some("Deathmatch"),
+        "2" => Some("Team Deathmatch"),
+        "3" => Some("Capture the Flag"),
+        "4" => Some("Horde"),
+        _ => None,
+    }
+}
+
+/// Formats an uptime like `3d 4h`, `2h 05m` or `12m`.
+pub fn format_uptime(uptime: Duration) -> String {
+    let minutes = uptime.as_secs() / 60;
+    let (days, hours, minutes) = (minutes / (60 * 24), minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// Everything known about a server, parts that aren't known are left out of the line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Status {
+    pub hostname: Option<String>,
+    pub map: Option<String>,
+    pub gametype: Option<String>,
+    pub players: Option<usize>,
+    pub maxplayers: Option<u32>,
+    pub uptime: Option<Duration>,
+    pub latency: Option<Duration>,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let players = match (self.players, self.maxplayers) {
+            (Some(players), Some(max)) => Some(format!("{players}/{max} players")),
+            (Some(players), None) => Some(format!("{players} players")),
+            (None, Some(max)) => Some(format!("?/{max} players")),
+            (None, None) => None,
+        };
+        let parts = [
+            self.hostname.clone(),
+            self.map.clone(),
+            self.gametype.clone(),
+            players,
+            self.uptime
+                .map(|uptime| format!("up {}", format_uptime(uptime))),
+            self.latency
+                .map(|latency| format!("{} ms", latency.as_millis())),
+        ];
+        let parts: Vec<String> = parts.into_iter().flatten().collect();
+        write!(f, "{}", parts.join(" | "))
+    }
+}
+
+/// How long the server took to answer, its hostname from the answer and the map it's on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ping {
+    pub latency: Duration,
+    pub hostname: Option<String>,
+    /// `None` when the server doesn't know `currentmap`
+    pub map: Option<String>,
+}
+
+/// Reads the value out of what a server prints for a cvar, like `"sv_hostname" is "Duel"`.
+pub fn parse_cvar_value(text: &str) -> Option<&str> {
+    let (_, value) = text.trim_end().split_once("\" is \"")?;
+    value.strip_suffix('"')
+}
+
+/// Reads the map out of what `currentmap` prints, like `MAP07` or `Current map: MAP07`.
+pub fn parse_current_map(text: &str) -> Option<&str> {
+    let text = text.trim();
+    if text.starts_with("Unknown command") {
+        return None;
+    }
+    let map = text.rsplit(char::is_whitespace).next()?;
+    let lump = !map.is_empty()
+        && map.len() <= 8
+        && map.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    lump.then_some(map)
+}
+
+async fn print_reply(socket: &RCONSocket, command: &str) -> Result<Option<String>, RCONError> {
+    let reply = socket
+        .request(ClientMessageType::Command(command.to_string()))
+        .await?;
+    Ok(match reply.content {
+        ServerMessageType::Print { text, .. } => Some(text),
+        _ => None,
+    })
+}
+
+/// Asks for `sv_hostname`, which every server has and answers right away, and times
+/// how long the answer takes. Then asks for the map, which doesn't count towards the
+/// latency and is left out if the server can't say.
+pub async fn ping(socket: &RCONSocket) -> Result<Ping, RCONError> {
+    let started = Instant::now();
+    let hostname = print_reply(socket, "sv_hostname").await?;
+    let latency = started.elapsed();
+    let map = match print_reply(socket, "currentmap").await {
+        Ok(text) => text
+            .as_deref()
+            .and_then(parse_current_map)
+            .map(str::to_string),
+        Err(e) => {
+            log::debug!("Could not ask for the current map: {e}");
+            None
+        }
+    };
+    Ok(Ping {
+        latency,
+        hostname: hostname
+            .as_deref()
+            .and_then(parse_cvar_value)
+            .map(str::to_string),
+        map,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_line() {
+        let status = Status {
+            hostname: Some("Duel".to_string()),
+            map: Some("MAP07".to_string()),
+            gametype: gametype_name("1").map(str::to_string),
+            players: Some(2),
+            maxplayers: Some(8),
+            uptime: Some(Duration::from_secs(3 * 3600 + 5 * 60 + 59)),
+            latency: Some(Duration::from_millis(42)),
+        };
+        assert_eq!(
+            status.to_string(),
+            "Duel | MAP07 | Deathmatch | 2/8 players | up 3h 05m | 42 ms"
+        );
+        let status = Status {
+            hostname: Some("Duel".to_string()),
+            maxplayers: Some(8),
+            ..Default::default()
+        };
+        assert_eq!(status.to_string(), "Duel | ?/8 players");
+        assert_eq!(Status::default().to_string(), "");
+    }
+
+    #[test]
+    fn uptimes_and_values() {
+        assert_eq!(format_uptime(Duration::from_secs(59)), "0m");
+        assert_eq!(format_uptime(Duration::from_secs(12 * 60)), "12m");
+        assert_eq!(format_uptime(Duration::from_secs(26 * 3600)), "1d 2h");
+        assert_eq!(
+            parse_cvar_value("\"sv_hostname\" is \"My \"cool\" server\"\n"),
+            Some("My \"cool\" server")
+        );
+        assert_eq!(parse_cvar_value("Unknown command sv_nope"), None);
+        assert_eq!(gametype_name("9"), None);
+        assert_eq!(parse_current_map("MAP07\n"), Some("MAP07"));
+        assert_eq!(parse_current_map("Current map: E1M1"), Some("E1M1"));
+        assert_eq!(parse_current_map("Unknown command currentmap"), None);
+        assert_eq!(parse_current_map("Not in a level right now."), None);
+        assert_eq!(parse_current_map(""), None);
+    }
+}
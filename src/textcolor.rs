@@ -0,0 +1,302 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! Odamex text color escapes, like `\cd` or `\c[Red]`.
+//!
+//! An escape starts with the `\x1c` control character, or with a literal `\c` in text
+//! that hasn't been through the engine's string parsing, and is followed by either a
+//! single color letter or a color name in brackets.
+//!
+//! A literal `\c` is also how paths like `C:\config` and words like `\cool` start, so
+//! it's only an escape when a known code follows it, and a letter code only when it
+//! isn't followed by more lowercase letters. Write `\cgRed` or `\c[Red]red`, not
+//! `\cgred`.
+
+/// The control character Odamex uses to start a color escape.
+pub const ESCAPE: char = '\x1c';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextColor {
+    Brick,
+    Tan,
+    Gray,
+    Green,
+    Brown,
+    Gold,
+    Red,
+    Blue,
+    Orange,
+    White,
+    Yellow,
+    Black,
+    LightBlue,
+    Cream,
+    Olive,
+    DarkGreen,
+    DarkRed,
+    DarkBrown,
+    Purple,
+    DarkGray,
+    Cyan,
+}
+
+/// Every color with its escape letter and name. `l` is the untranslated font, which
+/// isn't a color of its own and is handled as [`Escape::Normal`].
+const COLORS: [(char, &str, TextColor); 21] = [
+    ('a', "Brick", TextColor::Brick),
+    ('b', "Tan", TextColor::Tan),
+    ('c', "Gray", TextColor::Gray),
+    ('d', "Green", TextColor::Green),
+    ('e', "Brown", TextColor::Brown),
+    ('f', "Gold", TextColor::Gold),
+    ('g', "Red", TextColor::Red),
+    ('h', "Blue", TextColor::Blue),
+    ('i', "Orange", TextColor::Orange),
+    ('j', "White", TextColor::White),
+    ('k', "Yellow", TextColor::Yellow),
+    ('m', "Black", TextColor::Black),
+    ('n', "LightBlue", TextColor::LightBlue),
+    ('o', "Cream", TextColor::Cream),
+    ('p', "Olive", TextColor::Olive),
+    ('q', "DarkGreen", TextColor::DarkGreen),
+    ('r', "DarkRed", TextColor::DarkRed),
+    ('s', "DarkBrown", TextColor::DarkBrown),
+    ('t', "Purple", TextColor::Purple),
+    ('u', "DarkGray", TextColor::DarkGray),
+    ('v', "Cyan", TextColor::Cyan),
+];
+
+impl TextColor {
+    pub fn from_code(code: char) -> Option<Self> {
+        let code = code.to_ascii_lowercase();
+        COLORS
+            .iter()
+            .find(|(c, _, _)| *c == code)
+            .map(|(_, _, color)| *color)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase().replace("grey", "gray");
+        COLORS
+            .iter()
+            .find(|(_, n, _)| n.eq_ignore_ascii_case(&name))
+            .map(|(_, _, color)| *color)
+    }
+
+    /// Roughly the color the game draws text in, for terminals that can show it.
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            TextColor::Brick => (0xcc, 0x44, 0x44),
+            TextColor::Tan => (0xd2, 0xb4, 0x8c),
+            TextColor::Gray => (0xa0, 0xa0, 0xa0),
+            TextColor::Green => (0x55, 0xff, 0x55),
+            TextColor::Brown => (0x99, 0x66, 0x33),
+            TextColor::Gold => (0xff, 0xcc, 0x00),
+            TextColor::Red => (0xff, 0x33, 0x33),
+            TextColor::Blue => (0x55, 0x55, 0xff),
+            TextColor::Orange => (0xff, 0x99, 0x00),
+            TextColor::White => (0xff, 0xff, 0xff),
+            TextColor::Yellow => (0xff, 0xff, 0x55),
+            TextColor::Black => (0x40, 0x40, 0x40),
+            TextColor::LightBlue => (0x99, 0xcc, 0xff),
+            TextColor::Cream => (0xff, 0xcc, 0x99),
+            TextColor::Olive => (0x7b, 0x7f, 0x50),
+            TextColor::DarkGreen => (0x00, 0x8c, 0x00),
+            TextColor::DarkRed => (0x8b, 0x00, 0x00),
+            TextColor::DarkBrown => (0x66, 0x33, 0x00),
+            TextColor::Purple => (0xcc, 0x00, 0xcc),
+            TextColor::DarkGray => (0x80, 0x80, 0x80),
+            TextColor::Cyan => (0x00, 0xdd, 0xdd),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Escape {
+    /// Back to the normal color of the message
+    Normal,
+    Bold,
+    /// The color the player picked for chat messages
+    Chat,
+    /// The color the player picked for team chat messages
+    TeamChat,
+    Color(TextColor),
+}
+
+impl Escape {
+    fn from_code(code: char) -> Option<Self> {
+        match code {
+            '-' | 'l' | 'L' => Some(Escape::Normal),
+            '+' => Some(Escape::Bold),
+            '*' => Some(Escape::Chat),
+            '!' => Some(Escape::TeamChat),
+            code => TextColor::from_code(code).map(Escape::Color),
+        }
+    }
+}
+
+/// A piece of text and the escape that was last in effect before it,
+/// `None` if there wasn't one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span<'a> {
+    pub escape: Option<Escape>,
+    pub text: &'a str,
+}
+
+/// Finds the next escape, returning where it starts and where its code starts.
+fn find_escape(text: &str) -> Option<(usize, usize)> {
+    for (i, c) in text.char_indices() {
+        if c == ESCAPE {
+            return Some((i, i + 1));
+        }
+        if c == '\\' && text[i + 1..].starts_with('c') && literal_code(&text[i + 2..]) {
+            return Some((i, i + 2));
+        }
+    }
+    None
+}
+
+/// Whether the text after a literal `\c` starts with a code, rather than the rest of a
+/// word or a path.
+fn literal_code(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some('[') => read_code(text).0.is_some(),
+        Some(code) if code.is_ascii_alphabetic() => {
+            Escape::from_code(code).is_some()
+                && !chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        }
+        Some(code) => Escape::from_code(code).is_some(),
+        None => false,
+    }
+}
+
+/// Reads the code of an escape, returning it and the text after it. Unknown codes
+/// give `None`, the game skips over those without changing the color.
+fn read_code(text: &str) -> (Option<Escape>, &str) {
+    match text.chars().next() {
+        None => (None, text),
+        Some('[') => match text.find(']') {
+            Some(end) => (
+                TextColor::from_name(&text[1..end]).map(Escape::Color),
+                &text[end + 1..],
+            ),
+            None => (None, &text[1..]),
+        },
+        Some(code) => (Escape::from_code(code), &text[code.len_utf8()..]),
+    }
+}
+
+/// Splits text into spans at its color escapes, leaving the escapes out.
+pub fn parse(text: &str) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    let mut escape = None;
+    let mut rest = text;
+    loop {
+        let Some((start, code)) = find_escape(rest) else {
+            if !rest.is_empty() {
+                spans.push(Span { escape, text: rest });
+            }
+            return spans;
+        };
+        if start > 0 {
+            spans.push(Span {
+                escape,
+                text: &rest[..start],
+            });
+        }
+        let (next, remaining) = read_code(&rest[code..]);
+        if next.is_some() {
+            escape = next;
+        }
+        rest = remaining;
+    }
+}
+
+/// Removes the color escapes from text, for places that can't show colors.
+pub fn strip(text: &str) -> String {
+    parse(text).iter().map(|span| span.text).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text() {
+        assert_eq!(
+            parse("no colors here"),
+            vec![Span {
+                escape: None,
+                text: "no colors here"
+            }]
+        );
+        assert_eq!(parse(""), vec![]);
+    }
+
+    #[test]
+    fn letter_and_named_escapes() {
+        assert_eq!(
+            parse("\\cdGreen \\c[Red]red\x1c-normal"),
+            vec![
+                Span {
+                    escape: Some(Escape::Color(TextColor::Green)),
+                    text: "Green "
+                },
+                Span {
+                    escape: Some(Escape::Color(TextColor::Red)),
+                    text: "red"
+                },
+                Span {
+                    escape: Some(Escape::Normal),
+                    text: "normal"
+                },
+            ]
+        );
+        assert_eq!(
+            parse("\x1c[darkgrey]x")[0].escape,
+            Some(Escape::Color(TextColor::DarkGray))
+        );
+        assert_eq!(parse("\x1c*chat")[0].escape, Some(Escape::Chat));
+    }
+
+    #[test]
+    fn unknown_escapes_are_dropped() {
+        assert_eq!(strip("a\x1czb\x1c[Nope]c\x1c[d"), "abcd");
+        assert_eq!(strip("trailing\x1c"), "trailing");
+        assert_eq!(parse("\x1czx")[0].escape, None);
+    }
+
+    #[test]
+    fn literal_backslashes_stay() {
+        for text in [
+            "a\\czb\\c[Nope]c\\c[d",
+            "C:\\config\\odasrv.cfg",
+            "\\cool",
+            "C:\\odamex\\",
+            "trailing\\c",
+        ] {
+            assert_eq!(strip(text), text);
+        }
+        assert_eq!(strip("\\cdBob \\c-x \\c[gold]y \\cg1"), "Bob x y 1");
+    }
+
+    #[test]
+    fn strip_escapes() {
+        assert_eq!(
+            strip("\x1cdPlayer\x1c- was fragged by \\c[Gold]Other"),
+            "Player was fragged by Other"
+        );
+    }
+}
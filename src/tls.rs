@@ -0,0 +1,175 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! TLS settings for `wss://` connections.
+
+use crate::config::TlsConfig;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::WebPkiSupportedAlgorithms;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("Could not read CA file {0}: {1}")]
+    CAFileError(PathBuf, rustls::pki_types::pem::Error),
+    #[error("CA file {0} contains no usable certificates")]
+    NoCertificates(PathBuf),
+    #[error("Invalid certificate fingerprint, expected 64 hex digits")]
+    InvalidFingerprint,
+    #[error("TLS setup failed: {0}")]
+    RustlsError(#[from] rustls::Error),
+}
+
+/// Builds the rustls client configuration for a `wss://` connection.
+///
+/// With a fingerprint the server's certificate is pinned and no chain validation is done,
+/// which is what makes self-signed certificates work. Otherwise the system roots are
+/// trusted, plus the certificates in the CA file if there is one.
+pub fn client_config(tls: &TlsConfig) -> Result<Arc<ClientConfig>, TlsError> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?;
+
+    let config = if let Some(fingerprint) = &tls.fingerprint {
+        let verifier = FingerprintVerifier {
+            fingerprint: parse_fingerprint(fingerprint)?,
+            algorithms: provider.signature_verification_algorithms,
+        };
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth()
+    } else {
+        builder
+            .with_root_certificates(root_store(tls)?)
+            .with_no_client_auth()
+    };
+    Ok(Arc::new(config))
+}
+
+fn root_store(tls: &TlsConfig) -> Result<RootCertStore, TlsError> {
+    let mut roots = RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+    for error in native.errors {
+        log::warn!("Could not load a system certificate: {error}");
+    }
+    roots.add_parsable_certificates(native.certs);
+
+    if let Some(ca_file) = &tls.ca_file {
+        let certs = CertificateDer::pem_file_iter(ca_file)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| TlsError::CAFileError(ca_file.clone(), e))?;
+        let (added, _) = roots.add_parsable_certificates(certs);
+        if added == 0 {
+            return Err(TlsError::NoCertificates(ca_file.clone()));
+        }
+    }
+    Ok(roots)
+}
+
+/// Parses a SHA-256 fingerprint written as hex, with or without `:` separators.
+pub fn parse_fingerprint(fingerprint: &str) -> Result<[u8; 32], TlsError> {
+    let digits: String = fingerprint.chars().filter(|c| *c != ':').collect();
+    if digits.len() != 64 || !digits.is_ascii() {
+        return Err(TlsError::InvalidFingerprint);
+    }
+    let mut bytes = [0; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16)
+            .map_err(|_| TlsError::InvalidFingerprint)?;
+    }
+    Ok(bytes)
+}
+
+/// Accepts exactly one certificate, identified by the SHA-256 hash of its DER encoding.
+#[derive(Debug)]
+struct FingerprintVerifier {
+    fingerprint: [u8; 32],
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for FingerprintVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if Sha256::digest(end_entity.as_ref()).as_slice() == self.fingerprint {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_fingerprint_formats() {
+        let plain = "00112233445566778899aabbccddeeff00112233445566778899AABBCCDDEEFF";
+        let colons = "00:11:22:33:44:55:66:77:88:99:AA:BB:CC:DD:EE:FF:00:11:22:33:44:55:66:77:88:99:AA:BB:CC:DD:EE:FF";
+        let parsed = parse_fingerprint(plain).unwrap();
+        assert_eq!(parsed[0], 0x00);
+        assert_eq!(parsed[10], 0xaa);
+        assert_eq!(parsed[31], 0xff);
+        assert_eq!(parse_fingerprint(colons).unwrap(), parsed);
+    }
+
+    #[test]
+    fn parse_fingerprint_invalid() {
+        assert!(parse_fingerprint("").is_err());
+        assert!(parse_fingerprint("0011").is_err());
+        assert!(
+            parse_fingerprint("zz112233445566778899aabbccddeeff00112233445566778899aabbccddeeff")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,121 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::console::{LineKind, print_to_console, print_to_tab};
+use super::{AppState, send_to_tab, tabs};
+use cursive::Cursive;
+use odarcon::alias::{self, Step};
+use odarcon::config::{Alias, AliasStep};
+use std::collections::BTreeMap;
+use tokio::sync::oneshot;
+
+/// Runs the alias the input starts with, if it starts with one. Returns whether it did,
+/// an alias that can't be run counts too since it shouldn't go to the server as it is.
+pub fn run_alias(s: &mut Cursive, input: &str) -> bool {
+    let input = input.trim();
+    let (name, args) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let expanded = s.user_data::<AppState>().and_then(|state| {
+        let server = state.tab().map(|tab| &tab.server);
+        let alias = alias::find(&state.config, server, name)?;
+        Some(alias::expand(name, alias, &alias::split_args(args)))
+    });
+    match expanded {
+        None => false,
+        Some(Ok(steps)) => {
+            run_steps(s, steps);
+            true
+        }
+        Some(Err(e)) => {
+            print_to_console(s, LineKind::Client, &format!("{e}\n"));
+            true
+        }
+    }
+}
+
+/// Sends the commands one after another in the background to the tab the alias was
+/// run in, stopping if one can't be sent or the tab is closed in the meantime.
+fn run_steps(s: &mut Cursive, steps: Vec<Step>) {
+    let Some(tab_id) = tabs::current_tab_id(s) else {
+        return;
+    };
+    let cb_sink = s.cb_sink().clone();
+    tokio::spawn(async move {
+        for step in steps {
+            let command = match step {
+                Step::Wait(duration) => {
+                    tokio::time::sleep(duration).await;
+                    continue;
+                }
+                Step::Command(command) => command,
+            };
+            let (sent_tx, sent_rx) = oneshot::channel();
+            let queued = cb_sink.send(Box::new(move |s: &mut Cursive| {
+                let sent = tabs::with_tab(s, tab_id, |_| ()).is_some() && {
+                    print_to_tab(s, tab_id, LineKind::Command, &command);
+                    send_to_tab(s, tab_id, &command)
+                };
+                if sent {
+                    tabs::with_tab(s, tab_id, |tab| tab.completions.note_command(&command));
+                }
+                let _ = sent_tx.send(sent);
+            }));
+            if queued.is_err() || sent_rx.await != Ok(true) {
+                break;
+            }
+        }
+    });
+}
+
+/// Describes an alias in a line, with its description or otherwise its steps.
+fn describe(alias: &Alias) -> String {
+    if !alias.description.is_empty() {
+        return alias.description.clone();
+    }
+    let steps: Vec<String> = alias
+        .steps
+        .iter()
+        .map(|step| match step {
+            AliasStep::Command(command) => command.clone(),
+            AliasStep::Wait(wait) => format!("(wait {}s)", wait.wait),
+        })
+        .collect();
+    steps.join("; ")
+}
+
+/// Lists the aliases that can be used on the current server.
+pub fn list_aliases(s: &mut Cursive) {
+    let aliases = s
+        .user_data::<AppState>()
+        .map(|state| {
+            let mut aliases: BTreeMap<String, String> = state
+                .config
+                .aliases
+                .iter()
+                .map(|(name, alias)| (name.to_lowercase(), format!("{name}: {}", describe(alias))))
+                .collect();
+            if let Some(tab) = state.tab() {
+                for (name, alias) in &tab.server.aliases {
+                    aliases.insert(name.to_lowercase(), format!("{name}: {}", describe(alias)));
+                }
+            }
+            aliases.into_values().collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let text = if aliases.is_empty() {
+        "No aliases, they can be added to the config file\n".to_string()
+    } else {
+        format!("Aliases:\n  {}\n", aliases.join("\n  "))
+    };
+    print_to_console(s, LineKind::Client, &text);
+}
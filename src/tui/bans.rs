@@ -0,0 +1,302 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::tabs::{current_tab_id, with_current_tab, with_tab};
+use super::{error_popup, send_command};
+use cursive::Cursive;
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::bans::{self, Ban, BanColumn, BanOrder};
+use odarcon::client::RCONError;
+use time::OffsetDateTime;
+
+const ADDRESS_WIDTH: usize = 17;
+const EXPIRES_WIDTH: usize = 21;
+
+/// The sort buttons over the list, with the width of their column.
+const COLUMNS: [(BanColumn, &str, &str, usize); 3] = [
+    (
+        BanColumn::Address,
+        "IP/mask",
+        "ban_sort_address",
+        ADDRESS_WIDTH,
+    ),
+    (
+        BanColumn::Expires,
+        "Expires",
+        "ban_sort_expires",
+        EXPIRES_WIDTH,
+    ),
+    (BanColumn::Reason, "Reason", "ban_sort_reason", 0),
+];
+
+/// Opens the ban list and asks the server for a fresh copy of it.
+pub fn bans_layer(siv: &mut Cursive) {
+    // the list is indented by the panel's border
+    let mut header = LinearLayout::horizontal().child(DummyView.fixed_width(1));
+    for (column, label, name, _) in COLUMNS {
+        header.add_child(Button::new_raw(label, move |s| sort_by(s, column)).with_name(name));
+    }
+
+    let bans = SelectView::<Ban>::new()
+        .on_submit(|s, ban: &Ban| ban_actions(s, ban.clone()))
+        .with_name("ban_list")
+        .scrollable()
+        .min_height(10);
+
+    let layout = LinearLayout::vertical()
+        .child(header)
+        .child(Panel::new(bans))
+        .child(TextView::new("Loading ban list...").with_name("ban_status"));
+
+    siv.add_layer(
+        Dialog::around(layout)
+            .title("Ban List")
+            .button("Add", |s| edit_ban(s, "Add Ban", None))
+            .button("Refresh", request_bans)
+            .dismiss_button("Close")
+            .min_width(72),
+    );
+
+    rebuild_ban_list(siv);
+    request_bans(siv);
+}
+
+fn request_bans(siv: &mut Cursive) {
+    let Some((tab_id, Some(connection))) =
+        with_current_tab(siv, |tab| (tab.id, tab.connection.clone()))
+    else {
+        error_popup("Not connected to a server", siv);
+        return;
+    };
+    let cb_sink = siv.cb_sink().clone();
+    tokio::spawn(async move {
+        let bans = bans::fetch(&connection).await;
+        let _ = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            update_bans(s, tab_id, bans)
+        }));
+    });
+}
+
+/// Replaces the tab's stored ban list and redraws it if it's open for that tab.
+fn update_bans(siv: &mut Cursive, tab_id: u64, bans: Result<Vec<Ban>, RCONError>) {
+    let bans = match bans {
+        Ok(bans) => bans,
+        Err(e) => {
+            if current_tab_id(siv) == Some(tab_id) {
+                set_status(siv, &format!("Could not load ban list: {}", e));
+            }
+            return;
+        }
+    };
+    let count = bans.len();
+    with_tab(siv, tab_id, |tab| tab.bans = bans);
+    if current_tab_id(siv) != Some(tab_id) {
+        return;
+    }
+    match count {
+        1 => set_status(siv, "1 ban"),
+        count => set_status(siv, &format!("{count} bans")),
+    }
+    rebuild_ban_list(siv);
+}
+
+fn set_status(siv: &mut Cursive, status: &str) {
+    siv.call_on_name("ban_status", |v: &mut TextView| {
+        v.set_content(status);
+    });
+}
+
+fn sort_by(siv: &mut Cursive, column: BanColumn) {
+    with_current_tab(siv, |tab| tab.ban_order = tab.ban_order.toggle(column));
+    rebuild_ban_list(siv);
+}
+
+fn ban_label(ban: &Ban) -> String {
+    format!(
+        "{:<ADDRESS_WIDTH$}{:<EXPIRES_WIDTH$}{}",
+        ban.address,
+        ban.expires_label(),
+        ban.reason
+    )
+}
+
+/// The label of a sort button, padded to the width of its column since buttons
+/// center their labels.
+fn header_label(label: &str, width: usize, column: BanColumn, order: BanOrder) -> String {
+    let label = match (order.column == column, order.descending) {
+        (false, _) => label.to_string(),
+        (true, false) => format!("{label} ▲"),
+        (true, true) => format!("{label} ▼"),
+    };
+    format!("{label:<width$}")
+}
+
+/// Redraws the ban list from the current tab in the order picked with the headers.
+fn rebuild_ban_list(siv: &mut Cursive) {
+    let Some((mut bans, order)) = with_current_tab(siv, |tab| (tab.bans.clone(), tab.ban_order))
+    else {
+        return;
+    };
+    order.sort(&mut bans);
+    for (column, label, name, width) in COLUMNS {
+        siv.call_on_name(name, |v: &mut Button| {
+            v.set_label_raw(header_label(label, width, column, order))
+        });
+    }
+    siv.call_on_name("ban_list", |list: &mut SelectView<Ban>| {
+        // indexes change when bans are removed, the address stays the same
+        let selected = list.selection().map(|ban| ban.address.clone());
+        list.clear();
+        for ban in &bans {
+            list.add_item(ban_label(ban), ban.clone());
+        }
+        if let Some(index) = bans
+            .iter()
+            .position(|ban| Some(&ban.address) == selected.as_ref())
+        {
+            list.set_selection(index);
+        }
+    });
+}
+
+fn ban_actions(siv: &mut Cursive, ban: Ban) {
+    let title = format!("Ban on {}", ban.address);
+    let remove = ban.index;
+    let actions = Dialog::around(
+        LinearLayout::vertical()
+            .child(Button::new("Edit", move |s| {
+                s.pop_layer();
+                edit_ban(s, "Edit Ban", Some(ban.clone()));
+            }))
+            .child(Button::new("Remove", move |s| {
+                let areyousure = Dialog::text("Are you sure you want to lift this ban?")
+                    .title("Remove Ban")
+                    .dismiss_button("No")
+                    .button("Yes", move |s| {
+                        s.pop_layer();
+                        s.pop_layer();
+                        send_command(s, &bans::remove_command(remove));
+                        request_bans(s);
+                    })
+                    .padding_top(1);
+                s.add_layer(areyousure);
+            })),
+    )
+    .dismiss_button("Cancel")
+    .title(title)
+    .padding_top(1);
+    siv.add_layer(actions);
+}
+
+/// Opens the dialog for adding a ban, or for changing one which replaces it with
+/// a new ban.
+fn edit_ban(siv: &mut Cursive, title: &str, ban: Option<Ban>) {
+    let init = ban.clone().unwrap_or(Ban {
+        index: 0,
+        address: String::new(),
+        expires: None,
+        reason: String::new(),
+    });
+
+    let mut ban_settings = ListView::new();
+    ban_settings.add_child(
+        "IP/mask:",
+        EditView::new()
+            .content(init.address.clone())
+            .with_name("ban_address"),
+    );
+    if ban.is_some() {
+        ban_settings.add_child("Expires:", TextView::new(init.expires_label()));
+    }
+    ban_settings.add_child(
+        "Length (empty for good):",
+        EditView::new()
+            .content(init.remaining(OffsetDateTime::now_utc()))
+            .with_name("ban_length"),
+    );
+    ban_settings.add_child(
+        "Reason:",
+        EditView::new()
+            .content(init.reason.clone())
+            .with_name("ban_reason"),
+    );
+
+    let edit_dialog = Dialog::around(ban_settings)
+        .title(title)
+        .dismiss_button("Cancel")
+        .button("Save", move |s| {
+            let get = |s: &mut Cursive, name: &str| {
+                s.call_on_name(name, |v: &mut EditView| v.get_content())
+                    .unwrap_or_default()
+            };
+            let address = get(s, "ban_address").trim().to_string();
+            let length = get(s, "ban_length");
+            let reason = get(s, "ban_reason");
+            if !bans::valid_mask(&address) {
+                error_popup(
+                    "The IP/mask should be an address like 10.0.0.5,\nor a range like 10.0.*.*",
+                    s,
+                );
+                return;
+            }
+            s.pop_layer();
+            if let Some(ban) = &ban
+                && !send_command(s, &bans::remove_command(ban.index))
+            {
+                return;
+            }
+            send_command(s, &bans::add_command(&address, &length, &reason));
+            request_bans(s);
+        })
+        .min_width(56);
+    siv.add_layer(edit_dialog);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels() {
+        let ban = Ban {
+            index: 1,
+            address: "10.0.0.*".to_string(),
+            expires: None,
+            reason: "cheating".to_string(),
+        };
+        assert_eq!(
+            ban_label(&ban),
+            "10.0.0.*         never                cheating"
+        );
+        let order = BanOrder::default();
+        assert_eq!(
+            header_label("IP/mask", 12, BanColumn::Address, order),
+            "IP/mask ▲   "
+        );
+        assert_eq!(
+            header_label(
+                "IP/mask",
+                0,
+                BanColumn::Address,
+                order.toggle(BanColumn::Address)
+            ),
+            "IP/mask ▼"
+        );
+        assert_eq!(
+            header_label("Reason", 0, BanColumn::Reason, order),
+            "Reason"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::{AppState, error_popup};
+use cursive::Cursive;
+use cursive::theme::{ColorStyle, Effect, PaletteColor, Style};
+use cursive::utils::markup::StyledString;
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::broadcast::{self, BroadcastError};
+use odarcon::config::ServerConfig;
+use odarcon::textcolor;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// How long a server has to be quiet before its output is taken to be complete.
+const QUIET_TIME: Duration = Duration::from_secs(1);
+
+/// Numbers each broadcast, so results that arrive after their window was closed
+/// don't end up in the next one.
+static NEXT_BROADCAST: AtomicU64 = AtomicU64::new(0);
+
+fn server_checkbox_name(index: usize) -> String {
+    format!("broadcast_server_{index}")
+}
+
+/// Opens the dialog for picking servers and the command to send them.
+pub fn broadcast_dialog(s: &mut Cursive) {
+    let Some(servers) = s
+        .user_data::<AppState>()
+        .map(|state| state.config.servers.clone())
+    else {
+        return;
+    };
+    if servers.is_empty() {
+        error_popup("There are no saved servers to broadcast to", s);
+        return;
+    }
+
+    let mut list = ListView::new();
+    for (i, server) in servers.iter().enumerate() {
+        let label = if server.tags.is_empty() {
+            server.name.clone()
+        } else {
+            format!("{} [{}]", server.name, server.tags.join(", "))
+        };
+        list.add_child(&label, Checkbox::new().with_name(server_checkbox_name(i)));
+    }
+
+    let mut groups = LinearLayout::horizontal()
+        .child(TextView::new("Select: "))
+        .child(Button::new_raw("[all] ", |s| check_servers(s, |_| true)))
+        .child(Button::new_raw("[none] ", uncheck_servers));
+    for tag in broadcast::all_tags(&servers) {
+        let label = format!("[{tag}] ");
+        groups.add_child(Button::new_raw(label, move |s| {
+            check_servers(s, |server| {
+                server.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag))
+            })
+        }));
+    }
+
+    let layout = LinearLayout::vertical()
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new("Command: "))
+                .child(
+                    EditView::new()
+                        .on_submit(|s, _| send(s))
+                        .with_name("broadcast_command")
+                        .full_width(),
+                ),
+        )
+        .child(DummyView.fixed_height(1))
+        .child(groups)
+        .child(Panel::new(list.scrollable().max_height(15)));
+
+    s.add_layer(
+        Dialog::around(layout)
+            .title("Broadcast")
+            .dismiss_button("Cancel")
+            .button("Send", send)
+            .min_width(60),
+    );
+}
+
+/// Checks the servers `selected` is true for, keeping the ones that already are.
+fn check_servers(s: &mut Cursive, selected: impl Fn(&ServerConfig) -> bool) {
+    let Some(servers) = s
+        .user_data::<AppState>()
+        .map(|state| state.config.servers.clone())
+    else {
+        return;
+    };
+    for (i, server) in servers.iter().enumerate() {
+        if selected(server) {
+            s.call_on_name(&server_checkbox_name(i), |v: &mut Checkbox| v.check());
+        }
+    }
+}
+
+fn uncheck_servers(s: &mut Cursive) {
+    let count = s
+        .user_data::<AppState>()
+        .map_or(0, |state| state.config.servers.len());
+    for i in 0..count {
+        s.call_on_name(&server_checkbox_name(i), |v: &mut Checkbox| v.uncheck());
+    }
+}
+
+fn send(s: &mut Cursive) {
+    let command = s
+        .call_on_name("broadcast_command", |v: &mut EditView| v.get_content())
+        .unwrap_or_default();
+    let command = command.trim().to_string();
+    let servers: Vec<ServerConfig> = s
+        .user_data::<AppState>()
+        .map(|state| state.config.servers.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            s.call_on_name(&server_checkbox_name(*i), |v: &mut Checkbox| v.is_checked())
+                .unwrap_or(false)
+        })
+        .map(|(_, server)| server)
+        .collect();
+    if command.is_empty() {
+        error_popup("Type a command to send", s);
+        return;
+    }
+    if servers.is_empty() {
+        error_popup("Select at least one server", s);
+        return;
+    }
+    s.pop_layer();
+    results_layer(s, &command, servers);
+}
+
+fn result_names(broadcast_id: u64, index: usize) -> (String, String) {
+    (
+        format!("broadcast_{broadcast_id}_panel_{index}"),
+        format!("broadcast_{broadcast_id}_output_{index}"),
+    )
+}
+
+/// Shows a column for every server that fills in as its output arrives.
+fn results_layer(s: &mut Cursive, command: &str, servers: Vec<ServerConfig>) {
+    let broadcast_id = NEXT_BROADCAST.fetch_add(1, Ordering::Relaxed);
+    let mut columns = LinearLayout::horizontal();
+    for (i, server) in servers.iter().enumerate() {
+        let (panel_name, output_name) = result_names(broadcast_id, i);
+        let output = TextView::new("Waiting for the server...")
+            .with_name(output_name)
+            .scrollable();
+        columns.add_child(
+            Panel::new(output)
+                .title(&server.name)
+                .with_name(panel_name)
+                .min_width(30)
+                .max_width(60)
+                .full_height(),
+        );
+    }
+    s.add_layer(
+        Dialog::around(columns.scrollable().scroll_x(true).scroll_y(false))
+            .title(format!("Broadcast: {command}"))
+            .dismiss_button("Close")
+            .full_screen(),
+    );
+
+    let cb_sink = s.cb_sink().clone();
+    for (i, server) in servers.into_iter().enumerate() {
+        let cb_sink = cb_sink.clone();
+        let command = command.to_string();
+        tokio::spawn(async move {
+            let output = broadcast::run_on(&server, &command, QUIET_TIME).await;
+            let _ = cb_sink.send(Box::new(move |s: &mut Cursive| {
+                show_result(s, broadcast_id, i, &server.name, output)
+            }));
+        });
+    }
+}
+
+fn show_result(
+    s: &mut Cursive,
+    broadcast_id: u64,
+    index: usize,
+    server: &str,
+    output: Result<String, BroadcastError>,
+) {
+    let (panel_name, output_name) = result_names(broadcast_id, index);
+    let (title, content) = match output {
+        Ok(output) if output.is_empty() => (server.to_string(), StyledString::plain("(no output)")),
+        Ok(output) => (
+            server.to_string(),
+            StyledString::plain(textcolor::strip(&output)),
+        ),
+        Err(e) => (
+            format!("{server} (failed)"),
+            StyledString::styled(
+                e.to_string(),
+                Style::from(ColorStyle::front(PaletteColor::TitlePrimary)).combine(Effect::Bold),
+            ),
+        ),
+    };
+    s.call_on_name(&output_name, |v: &mut TextView| v.set_content(content));
+    s.call_on_name(
+        &panel_name,
+        |v: &mut Panel<ScrollView<NamedView<TextView>>>| v.set_title(title),
+    );
+}
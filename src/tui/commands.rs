@@ -0,0 +1,536 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+//! Commands for odarcon itself, typed into the console with a `:` in front.
+//! They never reach the server.
+
+use super::AppState;
+use super::aliases;
+use super::console::{self, FILTERS, LineKind, print_to_console};
+use super::tabs::{self, with_current_tab};
+use cursive::Cursive;
+use odarcon::config::ServerConfig;
+use odarcon::protocol::PrintLevel;
+use odarcon::sessionlog::SessionLog;
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+/// Every command with how it's used and what it does, for `:help`.
+const HELP: [(&str, &str, &str); 11] = [
+    (
+        "help",
+        ":help [command]",
+        "List the commands, or explain one",
+    ),
+    (
+        "connect",
+        ":connect [server | host[:port] [password]]",
+        "Connect this tab to a saved server or an address, or reconnect it",
+    ),
+    (
+        "open",
+        ":open <server | host[:port] [password]>",
+        "Open a saved server or an address in a new tab",
+    ),
+    (
+        "tab",
+        ":tab [number]",
+        "Switch to a tab, or list them. Alt+Left/Right switch too",
+    ),
+    ("disconnect", ":disconnect", "Disconnect and close this tab"),
+    ("clear", ":clear", "Clear the console"),
+    (
+        "log",
+        ":log [on | off]",
+        "Start or stop the session log, or show where it's written",
+    ),
+    (
+        "filter",
+        ":filter <kind | all> [show | hide]",
+        "Toggle a kind of line, like chat or status, or show everything",
+    ),
+    (
+        "save",
+        ":save <file>",
+        "Save what's in the console to a file, without colors",
+    ),
+    (
+        "set",
+        ":set <colorize | logging> <on | off>",
+        "Change a setting and save it to the config",
+    ),
+    (
+        "aliases",
+        ":aliases",
+        "List the aliases that can be used on this server",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Setting {
+    Colorize,
+    Logging,
+}
+
+#[derive(Debug, PartialEq)]
+enum ClientCommand {
+    Help(Option<String>),
+    /// `None` reconnects to the current server
+    Connect(Option<String>),
+    Open(String),
+    /// `None` lists the tabs, numbered from 1
+    Tab(Option<usize>),
+    Disconnect,
+    Clear,
+    /// `None` shows where the log is
+    Log(Option<bool>),
+    Filter {
+        /// `None` is every kind
+        kind: Option<LineKind>,
+        /// `None` toggles
+        visible: Option<bool>,
+    },
+    Save(PathBuf),
+    Set(Setting, bool),
+    Aliases,
+}
+
+fn usage(name: &str) -> String {
+    let usage = HELP
+        .iter()
+        .find(|(command, _, _)| *command == name)
+        .map_or(name, |(_, usage, _)| usage);
+    format!("Usage: {usage}")
+}
+
+fn parse_switch(word: &str) -> Option<bool> {
+    match word.to_ascii_lowercase().as_str() {
+        "on" | "yes" | "true" | "1" => Some(true),
+        "off" | "no" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Finds a kind of line by name, in the singular or plural.
+fn parse_kind(name: &str) -> Option<LineKind> {
+    let kind = match name
+        .to_ascii_lowercase()
+        .replace([' ', '_', '-'], "")
+        .as_str()
+    {
+        "pickup" | "pickups" => LineKind::Server(PrintLevel::Pickup),
+        "obituary" | "obituaries" => LineKind::Server(PrintLevel::Obituary),
+        "message" | "messages" | "high" => LineKind::Server(PrintLevel::High),
+        "chat" => LineKind::Server(PrintLevel::Chat),
+        "teamchat" => LineKind::Server(PrintLevel::TeamChat),
+        "serverchat" => LineKind::Server(PrintLevel::ServerChat),
+        "warning" | "warnings" => LineKind::Server(PrintLevel::Warning),
+        "error" | "errors" => LineKind::Server(PrintLevel::Error),
+        "status" | "client" => LineKind::Client,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+fn parse(input: &str) -> Result<ClientCommand, String> {
+    let input = input.trim();
+    let (name, args) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let args = args.trim();
+    let words: Vec<&str> = args.split_whitespace().collect();
+    let name = name.to_ascii_lowercase();
+    let command = match (name.as_str(), words.as_slice()) {
+        ("help" | "h" | "?", []) => ClientCommand::Help(None),
+        ("help" | "h" | "?", [command]) => {
+            ClientCommand::Help(Some(command.trim_start_matches(':').to_string()))
+        }
+        ("connect", []) => ClientCommand::Connect(None),
+        ("connect", _) => ClientCommand::Connect(Some(args.to_string())),
+        ("open", [_, ..]) => ClientCommand::Open(args.to_string()),
+        ("tab", []) => ClientCommand::Tab(None),
+        ("tab", [number]) => match number.parse() {
+            Ok(number) if number > 0 => ClientCommand::Tab(Some(number)),
+            _ => return Err(usage("tab")),
+        },
+        ("disconnect", []) => ClientCommand::Disconnect,
+        ("clear", []) => ClientCommand::Clear,
+        ("log", []) => ClientCommand::Log(None),
+        ("log", [switch]) => {
+            ClientCommand::Log(Some(parse_switch(switch).ok_or_else(|| usage("log"))?))
+        }
+        ("filter", ["all"]) => ClientCommand::Filter {
+            kind: None,
+            visible: Some(true),
+        },
+        ("filter", [kind, rest @ ..]) if rest.len() <= 1 => {
+            let kind = parse_kind(kind).ok_or_else(|| {
+                let kinds: Vec<_> = FILTERS
+                    .iter()
+                    .map(|(_, label, _)| label.to_lowercase().replace(' ', ""))
+                    .collect();
+                format!(
+                    "No such kind of line: {kind}, try one of: {}",
+                    kinds.join(", ")
+                )
+            })?;
+            let visible = match rest {
+                [] => None,
+                ["show"] => Some(true),
+                ["hide"] => Some(false),
+                _ => return Err(usage("filter")),
+            };
+            ClientCommand::Filter {
+                kind: Some(kind),
+                visible,
+            }
+        }
+        ("save", [_, ..]) => ClientCommand::Save(expand_home(args)),
+        ("set", [setting, value]) => {
+            let setting = match setting.to_ascii_lowercase().as_str() {
+                "colorize" | "colorize_logs" => Setting::Colorize,
+                "logging" | "logs" => Setting::Logging,
+                _ => return Err(format!("No such setting: {setting}")),
+            };
+            ClientCommand::Set(setting, parse_switch(value).ok_or_else(|| usage("set"))?)
+        }
+        ("aliases" | "alias", []) => ClientCommand::Aliases,
+        (name, _) if HELP.iter().any(|(command, _, _)| *command == name) => {
+            return Err(usage(name));
+        }
+        _ => return Err(format!("Unknown command :{name}, type :help for a list")),
+    };
+    Ok(command)
+}
+
+/// Turns a leading `~` into the home directory, since there's no shell to do it.
+pub fn expand_home(path: &str) -> PathBuf {
+    let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+    match (path.strip_prefix("~/"), home) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Hides the password of a `:connect` or `:open` to an address, so the input can be
+/// echoed and kept in the history. Saved servers are found by their name, which may
+/// have spaces in it, and have no password in the input to hide.
+pub fn hide_password<'a>(input: &'a str, servers: &[ServerConfig]) -> Cow<'a, str> {
+    let Some(command) = input.trim_start().strip_prefix(':') else {
+        return Cow::Borrowed(input);
+    };
+    let target = match parse(command) {
+        Ok(ClientCommand::Connect(Some(target)) | ClientCommand::Open(target)) => target,
+        _ => return Cow::Borrowed(input),
+    };
+    let saved = servers
+        .iter()
+        .any(|server| server.name.eq_ignore_ascii_case(target.trim()));
+    match target.split_once(' ') {
+        Some((address, password)) if !saved && !password.trim().is_empty() => {
+            let (name, _) = command
+                .trim()
+                .split_once(char::is_whitespace)
+                .unwrap_or_default();
+            Cow::Owned(format!(":{name} {address} ********"))
+        }
+        _ => Cow::Borrowed(input),
+    }
+}
+
+/// Reads a quick connect address like `host:port password`.
+fn parse_address(target: &str) -> Result<ServerConfig, String> {
+    let (address, password) = target.split_once(' ').unwrap_or((target, ""));
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse::<u16>()
+                .map_err(|_| format!("Invalid port: {port}"))?,
+        ),
+        None => (address, ServerConfig::default().port),
+    };
+    Ok(ServerConfig {
+        host: host.to_string(),
+        port,
+        password: password.trim().to_string(),
+        ..Default::default()
+    })
+}
+
+fn status(s: &mut Cursive, text: &str) {
+    print_to_console(s, LineKind::Client, &format!("{text}\n"));
+}
+
+/// Runs a client command, the input without the `:` in front.
+pub fn run(s: &mut Cursive, input: &str) {
+    match parse(input) {
+        Ok(command) => execute(s, command),
+        Err(e) => status(s, &e),
+    }
+}
+
+fn execute(s: &mut Cursive, command: ClientCommand) {
+    match command {
+        ClientCommand::Help(None) => {
+            let mut help = String::from("Client commands:");
+            for (_, usage, description) in HELP {
+                help.push_str(&format!("\n  {usage:<45} {description}"));
+            }
+            status(s, &help);
+        }
+        ClientCommand::Help(Some(name)) => {
+            match HELP.iter().find(|(command, _, _)| *command == name) {
+                Some((_, usage, description)) => status(s, &format!("{usage}\n  {description}")),
+                None => status(s, &format!("Unknown command :{name}")),
+            }
+        }
+        ClientCommand::Connect(target) => connect(s, target),
+        ClientCommand::Open(target) => match find_server(s, &target) {
+            Ok(server) => tabs::open_tab(s, &server),
+            Err(e) => status(s, &e),
+        },
+        ClientCommand::Tab(None) => list_tabs(s),
+        ClientCommand::Tab(Some(number)) => {
+            let count = s
+                .user_data::<AppState>()
+                .map_or(0, |state| state.tabs.len());
+            if number > count {
+                return status(s, &format!("There is no tab {number}"));
+            }
+            tabs::switch_to(s, number - 1);
+        }
+        ClientCommand::Aliases => aliases::list_aliases(s),
+        ClientCommand::Disconnect => tabs::close_current_tab(s),
+        ClientCommand::Clear => {
+            with_current_tab(s, |tab| tab.console.clear());
+            console::rerender(s);
+        }
+        ClientCommand::Log(enable) => log(s, enable),
+        ClientCommand::Filter { kind: None, .. } => {
+            console::show_all(s);
+            status(s, "Showing everything");
+        }
+        ClientCommand::Filter {
+            kind: Some(kind),
+            visible,
+        } => match visible {
+            Some(visible) => console::set_filter(s, kind, visible),
+            None => console::toggle_filter(s, kind),
+        },
+        ClientCommand::Save(path) => {
+            let text = s
+                .user_data::<AppState>()
+                .and_then(|state| Some(state.tab()?.console.plain_text(&state.config)))
+                .unwrap_or_default();
+            match std::fs::write(&path, &text) {
+                Ok(()) => status(
+                    s,
+                    &format!("Saved {} lines to {}", text.lines().count(), path.display()),
+                ),
+                Err(e) => status(s, &format!("Could not save to {}: {e}", path.display())),
+            }
+        }
+        ClientCommand::Set(setting, enabled) => {
+            let saved = s.with_user_data(|state: &mut AppState| {
+                match setting {
+                    Setting::Colorize => state.config.colorize_logs = enabled,
+                    Setting::Logging => state.config.logging.enabled = enabled,
+                }
+                state.config.save()
+            });
+            if let Some(Err(e)) = saved {
+                log::error!("Config file could not be saved: {e}");
+                status(s, &format!("Config file could not be saved: {e}"));
+            }
+            if setting == Setting::Colorize {
+                console::rerender(s);
+            }
+        }
+    }
+}
+
+/// Finds a saved server by name, or reads the target as an address.
+fn find_server(s: &mut Cursive, target: &str) -> Result<ServerConfig, String> {
+    let target = target.trim();
+    let saved = s.user_data::<AppState>().and_then(|state| {
+        state
+            .config
+            .servers
+            .iter()
+            .find(|server| server.name.eq_ignore_ascii_case(target))
+            .cloned()
+    });
+    match saved {
+        Some(server) => Ok(server),
+        None => parse_address(target),
+    }
+}
+
+fn connect(s: &mut Cursive, target: Option<String>) {
+    let server = match target {
+        Some(target) => find_server(s, &target),
+        None => with_current_tab(s, |tab| tab.server.clone())
+            .ok_or_else(|| "Not connected to a server yet".to_string()),
+    };
+    match server {
+        Ok(server) => tabs::replace_tab(s, &server),
+        Err(e) => status(s, &e),
+    }
+}
+
+fn list_tabs(s: &mut Cursive) {
+    let Some(lines) = s.user_data::<AppState>().map(|state| {
+        state
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| {
+                let marker = if i == state.current { ">" } else { " " };
+                format!("{marker} {}: {}", i + 1, tab.title())
+            })
+            .collect::<Vec<_>>()
+    }) else {
+        return;
+    };
+    status(s, &format!("Tabs:\n{}", lines.join("\n")));
+}
+
+fn log(s: &mut Cursive, enable: Option<bool>) {
+    let result = s.with_user_data(|state: &mut AppState| -> Result<String, String> {
+        let Some(tab) = state.tabs.get_mut(state.current) else {
+            return Err("Not connected to a server".to_string());
+        };
+        match (enable, &tab.session_log) {
+            (None, Some(log)) => Ok(format!("Logging to {}", log.path().display())),
+            (None, None) => Ok("Session logging is off".to_string()),
+            (Some(true), Some(log)) => Ok(format!("Already logging to {}", log.path().display())),
+            (Some(true), None) => {
+                let log = SessionLog::open(&tab.server, &state.config.logging)
+                    .map_err(|e| format!("Session log could not be opened: {e}"))?;
+                let message = format!("Logging to {}", log.path().display());
+                tab.session_log = Some(log);
+                Ok(message)
+            }
+            (Some(false), _) => {
+                tab.session_log = None;
+                Ok("Session logging stopped".to_string())
+            }
+        }
+    });
+    match result {
+        Some(Ok(message)) | Some(Err(message)) => status(s, &message),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(parse("help"), Ok(ClientCommand::Help(None)));
+        assert_eq!(
+            parse("help :save"),
+            Ok(ClientCommand::Help(Some("save".to_string())))
+        );
+        assert_eq!(
+            parse("connect A cool server"),
+            Ok(ClientCommand::Connect(Some("A cool server".to_string())))
+        );
+        assert_eq!(
+            parse("open 10.0.0.1:10666 hunter2"),
+            Ok(ClientCommand::Open("10.0.0.1:10666 hunter2".to_string()))
+        );
+        assert_eq!(parse("tab"), Ok(ClientCommand::Tab(None)));
+        assert_eq!(parse("tab 2"), Ok(ClientCommand::Tab(Some(2))));
+        assert_eq!(parse("CLEAR"), Ok(ClientCommand::Clear));
+        assert_eq!(parse("log off"), Ok(ClientCommand::Log(Some(false))));
+        assert_eq!(
+            parse("filter Team_Chat hide"),
+            Ok(ClientCommand::Filter {
+                kind: Some(LineKind::Server(PrintLevel::TeamChat)),
+                visible: Some(false),
+            })
+        );
+        assert_eq!(
+            parse("filter status"),
+            Ok(ClientCommand::Filter {
+                kind: Some(LineKind::Client),
+                visible: None,
+            })
+        );
+        assert_eq!(
+            parse("save /tmp/console log.txt"),
+            Ok(ClientCommand::Save(PathBuf::from("/tmp/console log.txt")))
+        );
+        assert_eq!(
+            parse("set colorize on"),
+            Ok(ClientCommand::Set(Setting::Colorize, true))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse("frobnicate"),
+            Err("Unknown command :frobnicate, type :help for a list".to_string())
+        );
+        assert_eq!(
+            parse("log maybe"),
+            Err("Usage: :log [on | off]".to_string())
+        );
+        assert_eq!(parse("save"), Err("Usage: :save <file>".to_string()));
+        assert_eq!(parse("tab 0"), Err("Usage: :tab [number]".to_string()));
+        assert_eq!(
+            parse("open"),
+            Err("Usage: :open <server | host[:port] [password]>".to_string())
+        );
+        assert!(parse("filter nothing").unwrap_err().contains("teamchat"));
+        assert_eq!(
+            parse("set volume on"),
+            Err("No such setting: volume".to_string())
+        );
+    }
+
+    #[test]
+    fn addresses() {
+        let server = parse_address("10.0.0.1:10666 hunter2").unwrap();
+        assert_eq!(
+            (server.host.as_str(), server.port, server.password.as_str()),
+            ("10.0.0.1", 10666, "hunter2")
+        );
+        assert_eq!(parse_address("example.com").unwrap().port, 11666);
+        assert!(parse_address("example.com:http").is_err());
+    }
+
+    #[test]
+    fn hidden_passwords() {
+        let servers = [ServerConfig {
+            name: "My Server".to_string(),
+            ..Default::default()
+        }];
+        let hide = |input| hide_password(input, &servers).into_owned();
+        assert_eq!(
+            hide(":connect 10.0.0.1:10666 hunter2"),
+            ":connect 10.0.0.1:10666 ********"
+        );
+        assert_eq!(
+            hide(" :OPEN   example.com  two words"),
+            ":OPEN example.com ********"
+        );
+        assert_eq!(hide(":open my server"), ":open my server");
+        assert_eq!(hide(":connect example.com"), ":connect example.com");
+        assert_eq!(hide(":save a b"), ":save a b");
+        assert_eq!(hide("rcon_password hunter2"), "rcon_password hunter2");
+    }
+}
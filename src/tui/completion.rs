@@ -0,0 +1,234 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::status;
+use super::tabs::{current_tab_id, with_current_tab, with_tab};
+use cursive::Cursive;
+use cursive::event::Key;
+use cursive::theme::{ColorStyle, PaletteColor};
+use cursive::utils::markup::StyledString;
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::commandlist::{self, Candidate, CommandList};
+use odarcon::config::ServerConfig;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// Commands that take a cvar name as their first argument.
+const CVAR_COMMANDS: [&str; 4] = ["get", "reset", "set", "toggle"];
+
+/// The commands and cvars of the server, and where they're cached.
+#[derive(Debug, Default)]
+pub struct Completions {
+    pub list: CommandList,
+    path: Option<PathBuf>,
+}
+
+/// Finds the word being completed in the input: its byte range and whether only cvars
+/// fit there. `None` when the cursor is in an argument that isn't a cvar.
+fn completion_target(input: &str, cursor: usize) -> Option<(Range<usize>, bool)> {
+    let cursor = cursor.min(input.len());
+    let start = input[..cursor]
+        .rfind(char::is_whitespace)
+        .map_or(0, |i| i + 1);
+    let before: Vec<&str> = input[..start].split_whitespace().collect();
+    match before.as_slice() {
+        [] => Some((start..cursor, false)),
+        [command] if CVAR_COMMANDS.contains(&command.to_ascii_lowercase().as_str()) => {
+            Some((start..cursor, true))
+        }
+        _ => None,
+    }
+}
+
+/// Loads the cached commands and cvars of the server that's being connected to,
+/// so completion works before the server has been asked.
+pub fn load_completions(server: &ServerConfig) -> Completions {
+    let path = CommandList::cache_path(server);
+    let list = match &path {
+        Some(path) => CommandList::load(path).unwrap_or_else(|e| {
+            log::warn!("Command cache could not be loaded: {e}");
+            CommandList::default()
+        }),
+        None => CommandList::default(),
+    };
+    Completions { list, path }
+}
+
+/// Asks the tab's server for its commands and cvars in the background, and caches them.
+pub fn refresh_completions(s: &mut Cursive, tab_id: u64) {
+    let Some(connection) = with_tab(s, tab_id, |tab| tab.connection.clone()).flatten() else {
+        return;
+    };
+    let cb_sink = s.cb_sink().clone();
+    tokio::spawn(async move {
+        let list = match CommandList::fetch(&connection).await {
+            Ok(list) if !list.is_empty() => list,
+            Ok(_) => {
+                log::warn!("Server did not list any commands or cvars");
+                return;
+            }
+            Err(e) => {
+                log::warn!("Commands and cvars could not be fetched: {e}");
+                return;
+            }
+        };
+        let _ = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            with_tab(s, tab_id, |tab| {
+                tab.completions.list = list;
+                tab.completions.save();
+            });
+            if current_tab_id(s) == Some(tab_id) {
+                status::update_header(s);
+            }
+        }));
+    });
+}
+
+impl Completions {
+    fn save(&self) {
+        if let Some(path) = &self.path
+            && let Err(e) = self.list.save(path)
+        {
+            log::warn!("Command cache could not be saved: {e}");
+        }
+    }
+
+    /// Replaces the cvars with ones that were just listed, keeping the commands.
+    pub fn update_cvars(&mut self, cvars: BTreeMap<String, String>) {
+        self.list.cvars = cvars;
+        self.save();
+    }
+
+    /// Keeps the cvar values shown in the popup up to date with commands that set them,
+    /// like `set sv_gravity 400` or `sv_gravity 400`.
+    pub fn note_command(&mut self, command: &str) {
+        let mut words = command.split_whitespace();
+        let (name, value) = match (words.next(), words.next(), words.next()) {
+            (Some(set), Some(name), Some(value)) if set.eq_ignore_ascii_case("set") => {
+                (name, value)
+            }
+            (Some(name), Some(value), None) => (name, value),
+            _ => return,
+        };
+        self.list.set_cvar(name, value.trim_matches('"'));
+    }
+}
+
+/// Completes the word under the cursor. A single candidate is filled in right away,
+/// with more than one the input is extended as far as they agree and a popup lists them.
+pub fn complete(s: &mut Cursive) {
+    let Some((input, cursor)) = s.call_on_name("input", |v: &mut EditView| {
+        (v.get_content(), v.get_cursor())
+    }) else {
+        return;
+    };
+    let Some((range, cvars_only)) = completion_target(&input, cursor) else {
+        return;
+    };
+    let candidates = with_current_tab(s, |tab| {
+        tab.completions
+            .list
+            .complete(&input[range.clone()], cvars_only)
+    })
+    .unwrap_or_default();
+    match candidates.as_slice() {
+        [] => {}
+        [candidate] => replace_word(s, range, &format!("{} ", candidate.name)),
+        _ => {
+            let prefix = commandlist::common_prefix(&candidates);
+            let range = if prefix.len() > range.len() {
+                replace_word(s, range.clone(), prefix);
+                range.start..range.start + prefix.len()
+            } else {
+                range
+            };
+            candidates_popup(s, range, candidates);
+        }
+    }
+}
+
+fn replace_word(s: &mut Cursive, range: Range<usize>, word: &str) {
+    s.call_on_name("input", |v: &mut EditView| {
+        let content = v.get_content();
+        let cursor = range.start + word.len();
+        v.set_content(format!(
+            "{}{}{}",
+            &content[..range.start],
+            word,
+            &content[range.end..]
+        ));
+        v.set_cursor(cursor);
+    });
+}
+
+fn candidates_popup(s: &mut Cursive, range: Range<usize>, candidates: Vec<Candidate>) {
+    let width = candidates
+        .iter()
+        .map(|candidate| candidate.name.len())
+        .max()
+        .unwrap_or(0);
+    let mut list = SelectView::<String>::new().on_submit(move |s, name: &String| {
+        s.pop_layer();
+        replace_word(s, range.clone(), &format!("{name} "));
+        if let Ok(cb) = s.focus_name("input") {
+            cb.process(s);
+        }
+    });
+    for candidate in candidates {
+        let mut label = StyledString::plain(format!("{:width$}  ", candidate.name));
+        match &candidate.value {
+            Some(value) => label.append_styled(
+                format!("\"{value}\""),
+                ColorStyle::front(PaletteColor::Secondary),
+            ),
+            None => label.append_styled("command", ColorStyle::front(PaletteColor::Tertiary)),
+        }
+        list.add_item(label, candidate.name);
+    }
+    let popup = OnEventView::new(
+        Dialog::around(
+            list.with_name("completion_list")
+                .scrollable()
+                .max_height(15),
+        )
+        .title("Completions")
+        .min_width(40),
+    )
+    .on_pre_event(Key::Tab, |s| {
+        s.call_on_name("completion_list", |v: &mut SelectView| v.select_down(1));
+    })
+    .on_pre_event(Key::Esc, |s| {
+        s.pop_layer();
+    });
+    s.add_layer(popup);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets() {
+        assert_eq!(completion_target("sv_gr", 5), Some((0..5, false)));
+        assert_eq!(completion_target("", 0), Some((0..0, false)));
+        assert_eq!(completion_target("set sv_gr", 9), Some((4..9, true)));
+        assert_eq!(completion_target("SET  sv", 7), Some((5..7, true)));
+        // the cursor can be in the middle of the input
+        assert_eq!(completion_target("sv_gr 800", 5), Some((0..5, false)));
+        assert_eq!(completion_target("say sv", 6), None);
+        assert_eq!(completion_target("set sv_gravity 8", 16), None);
+    }
+}
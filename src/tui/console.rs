@@ -0,0 +1,600 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::AppState;
+use super::search::{self, Search};
+use super::tabs::{self, with_current_tab};
+use cursive::Cursive;
+use cursive::event::Event;
+use cursive::theme::{Color, ColorStyle, ColorType, Effect, PaletteColor, Style};
+use cursive::utils::markup::StyledString;
+use cursive::view::{Nameable, ScrollStrategy};
+use cursive::views::{Checkbox, Dialog, ListView, NamedView, Panel, ScrollView, TextView};
+use odarcon::config::Config;
+use odarcon::protocol::PrintLevel;
+use odarcon::textcolor::{self, Escape};
+use std::collections::{HashSet, VecDeque};
+
+/// How many lines are kept for re-rendering the console, older ones are forgotten.
+const SCROLLBACK_LINES: usize = 10_000;
+
+/// How many forgotten lines can pile up in the console view before it's redrawn to
+/// drop them too. Redrawing for every line would mean re-rendering the whole scrollback
+/// for every print once it's full.
+const TRIM_EVERY: usize = 500;
+
+/// The kinds of lines that can be hidden, with their labels and Alt shortcuts.
+/// Echoed commands are always shown.
+pub const FILTERS: [(LineKind, &str, char); 9] = [
+    (LineKind::Server(PrintLevel::Pickup), "Pickups", '1'),
+    (LineKind::Server(PrintLevel::Obituary), "Obituaries", '2'),
+    (LineKind::Server(PrintLevel::High), "Messages", '3'),
+    (LineKind::Server(PrintLevel::Chat), "Chat", '4'),
+    (LineKind::Server(PrintLevel::TeamChat), "Team chat", '5'),
+    (LineKind::Server(PrintLevel::ServerChat), "Server chat", '6'),
+    (LineKind::Server(PrintLevel::Warning), "Warnings", '7'),
+    (LineKind::Server(PrintLevel::Error), "Errors", '8'),
+    (LineKind::Client, "Status", '9'),
+];
+
+/// Where a line in the console came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LineKind {
+    /// A command typed by the user, echoed back
+    Command,
+    /// A print from the server
+    Server(PrintLevel),
+    /// A status message from odarcon itself
+    Client,
+}
+
+/// Styles a line for the console. Commands get a `> ` prefix and client messages a `* `
+/// prefix, server prints have no prefix and are colored by level and their color escapes
+/// if `colorize_logs` is on.
+pub fn styled_line(kind: &LineKind, text: &str, config: &Config) -> StyledString {
+    let mut line = StyledString::new();
+    match kind {
+        LineKind::Command => {
+            line.append_styled(format!("> {text}"), Style::from(Effect::Bold));
+        }
+        LineKind::Server(level) if config.colorize_logs => {
+            let base = level_style(level, config);
+            for span in textcolor::parse(text) {
+                let style = match span.escape {
+                    None | Some(Escape::Normal) => base,
+                    Some(Escape::Bold) => base.combine(Effect::Bold),
+                    Some(Escape::Chat) => level_style(&PrintLevel::Chat, config),
+                    Some(Escape::TeamChat) => level_style(&PrintLevel::TeamChat, config),
+                    Some(Escape::Color(color)) => {
+                        let (r, g, b) = color.rgb();
+                        Style::from(ColorStyle::front(Color::Rgb(r, g, b)))
+                    }
+                };
+                line.append_styled(span.text, style);
+            }
+        }
+        LineKind::Server(_) => line.append_plain(textcolor::strip(text)),
+        LineKind::Client => {
+            line.append_styled(
+                format!("* {text}"),
+                ColorStyle::front(ColorType::Palette(PaletteColor::TitleSecondary)),
+            );
+        }
+    }
+    if !text.ends_with('\n') {
+        line.append_plain("\n");
+    }
+    line
+}
+
+fn level_style(level: &PrintLevel, config: &Config) -> Style {
+    match config.logcolors.get(level) {
+        Some(color) => Style::from(ColorStyle::front(Color::from(*color))),
+        None => Style::default(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleLine {
+    pub kind: LineKind,
+    pub text: String,
+}
+
+/// Everything printed to the console of a session, so it can be redrawn when
+/// the filters change.
+#[derive(Debug, Default)]
+pub struct Console {
+    lines: VecDeque<ConsoleLine>,
+    hidden: HashSet<LineKind>,
+    pub search: Option<Search>,
+    /// Length of the text currently in the console view, for keeping track of
+    /// where search matches are when lines are appended
+    rendered_len: usize,
+    /// How many lines were forgotten since the view was last redrawn, which it
+    /// still shows
+    forgotten: usize,
+}
+
+impl Console {
+    pub fn push(&mut self, kind: LineKind, text: &str) {
+        if self.lines.len() == SCROLLBACK_LINES {
+            self.lines.pop_front();
+            self.forgotten += 1;
+        }
+        self.lines.push_back(ConsoleLine {
+            kind,
+            text: text.to_string(),
+        });
+    }
+
+    pub fn is_visible(&self, kind: &LineKind) -> bool {
+        !self.hidden.contains(kind)
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        !self.hidden.is_empty()
+    }
+
+    pub fn set_visible(&mut self, kind: LineKind, visible: bool) {
+        if visible {
+            self.hidden.remove(&kind);
+        } else {
+            self.hidden.insert(kind);
+        }
+    }
+
+    pub fn show_all(&mut self) {
+        self.hidden.clear();
+    }
+
+    /// Forgets the scrollback, keeping the filters and what's searched for.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.rendered_len = 0;
+        self.forgotten = 0;
+        if let Some(search) = &mut self.search {
+            search.matches.clear();
+            search.current = None;
+        }
+    }
+
+    /// Whether enough lines were forgotten that the view should be redrawn without them.
+    fn needs_trim(&self) -> bool {
+        self.forgotten >= TRIM_EVERY
+    }
+
+    /// The lines that aren't filtered out, as they look in the console but without colors.
+    pub fn plain_text(&self, config: &Config) -> String {
+        self.lines
+            .iter()
+            .filter(|line| self.is_visible(&line.kind))
+            .map(|line| {
+                styled_line(&line.kind, &line.text, config)
+                    .source()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    /// Styles a line for display, with search matches highlighted. `None` if the line
+    /// is filtered out. Match positions are added to the search, relative to `offset`.
+    fn render(
+        &mut self,
+        line: &ConsoleLine,
+        config: &Config,
+        offset: usize,
+    ) -> Option<StyledString> {
+        if !self.is_visible(&line.kind) {
+            return None;
+        }
+        let styled = styled_line(&line.kind, &line.text, config);
+        let Some(search) = &mut self.search else {
+            return Some(styled);
+        };
+        let ranges = search.find(styled.source());
+        if search.only_matching && ranges.is_empty() {
+            return None;
+        }
+        let first = search.matches.len();
+        search
+            .matches
+            .extend(ranges.iter().map(|range| offset + range.start));
+        let current = search
+            .current
+            .and_then(|current| current.checked_sub(first))
+            .filter(|current| *current < ranges.len());
+        Some(search::highlight(&styled, &ranges, current))
+    }
+
+    /// Renders every retained line, resetting the search matches.
+    fn render_all(&mut self, config: &Config) -> StyledString {
+        let mut content = StyledString::new();
+        if let Some(search) = &mut self.search {
+            search.matches.clear();
+        }
+        // the newest match is only known once everything has been searched
+        let newest = self.search.as_ref().is_some_and(|s| s.current.is_none());
+        let lines = std::mem::take(&mut self.lines);
+        for line in &lines {
+            if let Some(styled) = self.render(line, config, content.source().len()) {
+                content.append(styled);
+            }
+        }
+        self.lines = lines;
+        if newest && let Some(search) = &mut self.search {
+            search.current = search.matches.len().checked_sub(1);
+            if search.current.is_some() {
+                return self.render_all(config);
+            }
+        }
+        self.rendered_len = content.source().len();
+        self.forgotten = 0;
+        content
+    }
+
+    /// Renders the retained lines once old ones were forgotten. The match that was
+    /// jumped to stays the same one, unless it was in a forgotten line.
+    fn trim(&mut self, config: &Config) -> StyledString {
+        let from_newest = self
+            .search
+            .as_ref()
+            .and_then(|search| search.matches.len().checked_sub(search.current?));
+        let content = self.render_all(config);
+        let (Some(from_newest), Some(search)) = (from_newest, &mut self.search) else {
+            return content;
+        };
+        let current = search.matches.len().saturating_sub(from_newest);
+        if search.current == Some(current) || search.matches.is_empty() {
+            return content;
+        }
+        search.current = Some(current);
+        self.render_all(config)
+    }
+}
+
+/// What printing a line did to the tab it was printed in.
+enum Printed {
+    /// The line goes at the end of the console view
+    Append(StyledString),
+    /// Old lines were forgotten, so the view is replaced instead of appended to
+    Redraw(StyledString),
+    /// The tab isn't shown, `true` if it just got marked as unread
+    Background(bool),
+}
+
+/// Prints a line in the tab that's shown.
+pub fn print_to_console(s: &mut Cursive, kind: LineKind, text: &str) {
+    if let Some(id) = tabs::current_tab_id(s) {
+        print_to_tab(s, id, kind, text);
+    }
+}
+
+/// Prints a line in a tab, which gets marked as unread if it's in the background.
+pub fn print_to_tab(s: &mut Cursive, tab_id: u64, kind: LineKind, text: &str) {
+    let Some(printed) = s.with_user_data(|state: &mut AppState| {
+        let current = state.tab().is_some_and(|tab| tab.id == tab_id);
+        let tab = state.tabs.iter_mut().find(|tab| tab.id == tab_id)?;
+        let console = &mut tab.console;
+        console.push(kind, text);
+        if !current {
+            let newly_unread = !tab.unread;
+            tab.unread = true;
+            return Some(Printed::Background(newly_unread));
+        }
+        if console.needs_trim() {
+            return Some(Printed::Redraw(console.trim(&state.config)));
+        }
+        let line = console.lines.back().cloned()?;
+        let styled = console.render(&line, &state.config, console.rendered_len)?;
+        console.rendered_len += styled.source().len();
+        Some(Printed::Append(styled))
+    }) else {
+        return;
+    };
+    match printed {
+        Some(Printed::Append(line)) => {
+            s.call_on_name("output", |v: &mut TextView| {
+                v.append(line);
+            });
+        }
+        Some(Printed::Redraw(content)) => {
+            s.call_on_name("output", |v: &mut TextView| v.set_content(content));
+        }
+        Some(Printed::Background(true)) => tabs::update_tab_bar(s),
+        Some(Printed::Background(false)) | None => {}
+    }
+}
+
+/// Redraws the whole console from the retained lines, after the filters, colors or
+/// search changed.
+pub fn rerender(s: &mut Cursive) {
+    let Some((content, filtered)) = s
+        .with_user_data(|state: &mut AppState| {
+            let tab = state.tabs.get_mut(state.current)?;
+            let content = tab.console.render_all(&state.config);
+            Some((content, tab.console.is_filtered()))
+        })
+        .flatten()
+    else {
+        return;
+    };
+    s.call_on_name("output", |v: &mut TextView| v.set_content(content));
+    s.call_on_name("console_panel", |v: &mut Panel<ConsoleScroll>| {
+        v.set_title(if filtered {
+            "Console (filtered)"
+        } else {
+            "Console"
+        })
+    });
+}
+
+/// The scrollable part of the console view, named `output_scroll`.
+pub type ConsoleScroll = NamedView<ScrollView<NamedView<TextView>>>;
+
+pub fn scroll_to_bottom(s: &mut Cursive) {
+    s.call_on_name(
+        "output_scroll",
+        |v: &mut ScrollView<NamedView<TextView>>| {
+            v.set_scroll_strategy(ScrollStrategy::StickToBottom)
+        },
+    );
+}
+
+pub fn set_filter(s: &mut Cursive, kind: LineKind, visible: bool) {
+    with_current_tab(s, |tab| tab.console.set_visible(kind, visible));
+    rerender(s);
+}
+
+pub fn toggle_filter(s: &mut Cursive, kind: LineKind) {
+    let visible = with_current_tab(s, |tab| tab.console.is_visible(&kind)).unwrap_or(false);
+    s.call_on_name(&filter_checkbox_name(&kind), |v: &mut Checkbox| {
+        v.set_checked(!visible)
+    });
+    set_filter(s, kind, !visible);
+}
+
+/// Turns every filter off, ticking the checkboxes if the filter dialog is open.
+pub fn show_all(s: &mut Cursive) {
+    for (kind, _, _) in FILTERS {
+        s.call_on_name(&filter_checkbox_name(&kind), |v: &mut Checkbox| {
+            v.set_checked(true)
+        });
+    }
+    with_current_tab(s, |tab| tab.console.show_all());
+    rerender(s);
+}
+
+fn filter_checkbox_name(kind: &LineKind) -> String {
+    format!("filter_{kind:?}")
+}
+
+/// The Alt+number shortcuts for toggling each filter.
+pub fn filter_shortcuts() -> impl Iterator<Item = (Event, LineKind)> {
+    FILTERS
+        .iter()
+        .map(|(kind, _, key)| (Event::AltChar(*key), kind.clone()))
+}
+
+/// Opens the dialog with a checkbox for each kind of line.
+pub fn filters_dialog(s: &mut Cursive) {
+    let Some(visible) = with_current_tab(s, |tab| {
+        FILTERS.map(|(kind, _, _)| tab.console.is_visible(&kind))
+    }) else {
+        return;
+    };
+    let mut filters = ListView::new();
+    for ((kind, label, key), visible) in FILTERS.into_iter().zip(visible) {
+        let name = filter_checkbox_name(&kind);
+        filters.add_child(
+            format!("{label} (Alt+{key})"),
+            Checkbox::new()
+                .with_checked(visible)
+                .on_change(move |s, checked| set_filter(s, kind.clone(), checked))
+                .with_name(name),
+        );
+    }
+    s.add_layer(
+        Dialog::around(filters)
+            .title("Show in console")
+            .button("Show all", show_all)
+            .dismiss_button("Close"),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(colorize_logs: bool) -> Config {
+        Config {
+            colorize_logs,
+            logcolors: HashMap::from([
+                (PrintLevel::Error, odarcon::config::Color::Rgb(255, 0, 0)),
+                (PrintLevel::Chat, odarcon::config::Color::Rgb(0, 255, 0)),
+            ]),
+            ..Config::empty()
+        }
+    }
+
+    #[test]
+    fn forgotten_lines_trim_the_view() {
+        let mut console = Console::default();
+        let config = Config::empty();
+        for i in 0..SCROLLBACK_LINES + TRIM_EVERY - 1 {
+            console.push(LineKind::Command, &i.to_string());
+        }
+        assert_eq!(console.lines.len(), SCROLLBACK_LINES);
+        assert!(!console.needs_trim());
+        console.push(LineKind::Command, "last");
+        assert!(console.needs_trim());
+        let rendered = console.render_all(&config);
+        assert!(!console.needs_trim());
+        assert_eq!(console.rendered_len, rendered.source().len());
+        assert!(rendered.source().starts_with(&format!("> {TRIM_EVERY}\n")));
+
+        // clearing forgets the lines that were waiting to be trimmed as well
+        for i in 0..TRIM_EVERY {
+            console.push(LineKind::Command, &i.to_string());
+        }
+        assert!(console.needs_trim());
+        console.clear();
+        assert!(!console.needs_trim());
+    }
+
+    #[test]
+    fn trimming_keeps_the_current_match() {
+        let mut console = Console::default();
+        let config = Config::empty();
+        console.push(LineKind::Command, "kick 1");
+        for i in 0..SCROLLBACK_LINES - 3 {
+            console.push(LineKind::Command, &i.to_string());
+        }
+        console.push(LineKind::Command, "kick 2");
+        console.push(LineKind::Command, "kick 3");
+        console.search = Some(Search::new("kick", false, false).unwrap());
+        console.render_all(&config);
+        // jump to the match in "kick 2"
+        console.search.as_mut().unwrap().current = Some(1);
+        console.render_all(&config);
+        for _ in 0..TRIM_EVERY {
+            console.push(LineKind::Command, "x");
+        }
+        let rendered = console.trim(&config);
+        let search = console.search.as_ref().unwrap();
+        assert_eq!(search.matches.len(), 2);
+        assert_eq!(search.current, Some(0));
+        let start = search.matches[0];
+        assert_eq!(&rendered.source()[start..start + 6], "kick 2");
+        assert_eq!(console.rendered_len, rendered.source().len());
+    }
+
+    #[test]
+    fn filters_hide_lines() {
+        let mut console = Console::default();
+        console.push(LineKind::Server(PrintLevel::Pickup), "ammo\n");
+        console.push(LineKind::Command, "status");
+        console.push(LineKind::Server(PrintLevel::Error), "oops\n");
+        console.set_visible(LineKind::Server(PrintLevel::Pickup), false);
+        assert!(console.is_filtered());
+        let config = Config::empty();
+        assert_eq!(console.render_all(&config).source(), "> status\noops\n");
+        console.set_visible(LineKind::Server(PrintLevel::Pickup), true);
+        assert_eq!(
+            console.render_all(&config).source(),
+            "ammo\n> status\noops\n"
+        );
+        assert!(!console.is_filtered());
+
+        console.set_visible(LineKind::Command, false);
+        console.set_visible(LineKind::Server(PrintLevel::Error), false);
+        console.show_all();
+        assert!(!console.is_filtered());
+    }
+
+    #[test]
+    fn plain_text_and_clear() {
+        let mut console = Console::default();
+        console.push(LineKind::Command, "say hi");
+        console.push(LineKind::Server(PrintLevel::Chat), "\x1cdPlayer\x1c*: hi\n");
+        console.push(LineKind::Client, "Connected\n");
+        console.set_visible(LineKind::Client, false);
+        assert_eq!(console.plain_text(&config(true)), "> say hi\nPlayer: hi\n");
+        console.clear();
+        assert_eq!(console.plain_text(&config(true)), "");
+        assert!(console.is_filtered());
+        assert_eq!(console.rendered_len, 0);
+    }
+
+    #[test]
+    fn search_only_matching() {
+        let mut console = Console::default();
+        console.push(LineKind::Server(PrintLevel::Chat), "Player: hi\n");
+        console.push(LineKind::Server(PrintLevel::Chat), "Other: hey\n");
+        console.push(LineKind::Server(PrintLevel::Chat), "Player: bye\n");
+        console.search = Some(Search::new("player", false, true).unwrap());
+        let content = console.render_all(&Config::empty());
+        assert_eq!(content.source(), "Player: hi\nPlayer: bye\n");
+        let search = console.search.as_ref().unwrap();
+        assert_eq!(search.matches, vec![0, 11]);
+        // starts at the newest match
+        assert_eq!(search.current, Some(1));
+    }
+
+    #[test]
+    fn scrollback_limit() {
+        let mut console = Console::default();
+        for i in 0..SCROLLBACK_LINES + 5 {
+            console.push(LineKind::Client, &i.to_string());
+        }
+        assert_eq!(console.lines.len(), SCROLLBACK_LINES);
+        assert_eq!(console.lines[0].text, "5");
+    }
+
+    #[test]
+    fn prefixes() {
+        let config = config(false);
+        assert_eq!(
+            styled_line(&LineKind::Command, "status", &config).source(),
+            "> status\n"
+        );
+        assert_eq!(
+            styled_line(&LineKind::Client, "Connecting...\n", &config).source(),
+            "* Connecting...\n"
+        );
+        assert_eq!(
+            styled_line(&LineKind::Server(PrintLevel::High), "hi\n", &config).source(),
+            "hi\n"
+        );
+    }
+
+    #[test]
+    fn colorize_by_level() {
+        let error = LineKind::Server(PrintLevel::Error);
+        let colored = styled_line(&error, "oops\n", &config(true));
+        let span = colored.spans().next().unwrap();
+        assert_eq!(
+            span.attr.color.front,
+            ColorType::Color(Color::Rgb(255, 0, 0))
+        );
+
+        // no configured color and colorize_logs off both fall back to plain text
+        let plain = styled_line(&error, "oops\n", &config(false));
+        assert_eq!(*plain.spans().next().unwrap().attr, Style::default());
+        let high = styled_line(&LineKind::Server(PrintLevel::High), "hi\n", &config(true));
+        assert_eq!(*high.spans().next().unwrap().attr, Style::default());
+    }
+
+    #[test]
+    fn color_escapes() {
+        let high = LineKind::Server(PrintLevel::High);
+        let line = styled_line(&high, "\\cgRed\x1c*chat\x1c-plain\n", &config(true));
+        let fronts: Vec<_> = line
+            .spans()
+            .map(|span| (span.content, span.attr.color.front))
+            .collect();
+        assert_eq!(
+            fronts,
+            vec![
+                ("Red", ColorType::Color(Color::Rgb(0xff, 0x33, 0x33))),
+                ("chat", ColorType::Color(Color::Rgb(0, 255, 0))),
+                ("plain\n", Style::default().color.front),
+            ]
+        );
+
+        // without colors the escapes are just removed
+        let line = styled_line(&high, "\\cgRed\x1c*chat\n", &config(false));
+        assert_eq!(line.source(), "Redchat\n");
+    }
+}
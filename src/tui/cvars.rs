@@ -0,0 +1,262 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::status;
+use super::tabs::{current_tab_id, with_current_tab, with_tab};
+use super::{error_popup, send_command};
+use cursive::Cursive;
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::client::RCONError;
+use odarcon::commandlist::{self, cvar_group};
+use std::collections::BTreeMap;
+
+/// Opens the cvar browser with the cvars that are known already, and asks the server
+/// for their current values.
+pub fn cvars_layer(siv: &mut Cursive) {
+    let filter = EditView::new()
+        .on_edit(|s, _, _| rebuild_cvar_list(s))
+        .with_name("cvar_filter");
+
+    // `None` is every group
+    let groups = SelectView::<Option<String>>::new()
+        .on_select(|s, _| rebuild_cvar_list(s))
+        .with_name("cvar_groups")
+        .scrollable()
+        .fixed_width(16);
+
+    let cvars = SelectView::<String>::new()
+        .on_submit(|s, name: &String| edit_cvar(s, name))
+        .with_name("cvar_list")
+        .scrollable()
+        .full_width();
+
+    let layout = LinearLayout::vertical()
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new("Filter: "))
+                .child(filter.full_width()),
+        )
+        .child(DummyView.fixed_height(1))
+        .child(
+            LinearLayout::horizontal()
+                .child(Panel::new(groups).title("Groups"))
+                .child(Panel::new(cvars).title("Cvars"))
+                .min_height(15),
+        )
+        .child(TextView::new("Loading cvars...").with_name("cvar_status"));
+
+    siv.add_layer(
+        Dialog::around(layout)
+            .title("Cvars")
+            .button("Refresh", request_cvars)
+            .dismiss_button("Close")
+            .min_width(80),
+    );
+
+    rebuild_groups(siv);
+    request_cvars(siv);
+}
+
+fn request_cvars(siv: &mut Cursive) {
+    let Some((tab_id, Some(connection))) =
+        with_current_tab(siv, |tab| (tab.id, tab.connection.clone()))
+    else {
+        error_popup("Not connected to a server", siv);
+        return;
+    };
+    let cb_sink = siv.cb_sink().clone();
+    tokio::spawn(async move {
+        let cvars = commandlist::fetch_cvars(&connection).await;
+        let _ = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            update_cvars(s, tab_id, cvars)
+        }));
+    });
+}
+
+/// Stores the values that were just listed with the tab's completions, and redraws the
+/// browser if it's open for that tab.
+fn update_cvars(
+    siv: &mut Cursive,
+    tab_id: u64,
+    cvars: Result<BTreeMap<String, String>, RCONError>,
+) {
+    let is_current = current_tab_id(siv) == Some(tab_id);
+    match cvars {
+        Ok(cvars) if cvars.is_empty() => {
+            if is_current {
+                set_status(siv, "The server did not list any cvars");
+            }
+        }
+        Ok(cvars) => {
+            let count = cvars.len();
+            with_tab(siv, tab_id, |tab| tab.completions.update_cvars(cvars));
+            if is_current {
+                set_status(siv, &format!("{} cvars", count));
+                rebuild_groups(siv);
+                status::update_header(siv);
+            }
+        }
+        Err(e) => {
+            if is_current {
+                set_status(siv, &format!("Could not load cvars: {}", e));
+            }
+        }
+    }
+}
+
+fn set_status(siv: &mut Cursive, status: &str) {
+    siv.call_on_name("cvar_status", |v: &mut TextView| {
+        v.set_content(status);
+    });
+}
+
+fn current_cvars(siv: &mut Cursive) -> BTreeMap<String, String> {
+    with_current_tab(siv, |tab| tab.completions.list.cvars.clone()).unwrap_or_default()
+}
+
+/// The groups of the cvars and how many cvars are in each, sorted by name.
+fn groups(cvars: &BTreeMap<String, String>) -> BTreeMap<String, usize> {
+    let mut groups = BTreeMap::new();
+    for name in cvars.keys() {
+        *groups
+            .entry(cvar_group(name).to_ascii_lowercase())
+            .or_default() += 1;
+    }
+    groups
+}
+
+/// Redraws the group list, keeping the group that's selected if it's still there.
+fn rebuild_groups(siv: &mut Cursive) {
+    let cvars = current_cvars(siv);
+    siv.call_on_name("cvar_groups", |list: &mut SelectView<Option<String>>| {
+        let selected = list.selection().and_then(|group| (*group).clone());
+        list.clear();
+        list.add_item(format!("All ({})", cvars.len()), None);
+        for (group, count) in groups(&cvars) {
+            let label = if group.is_empty() {
+                format!("Other ({count})")
+            } else {
+                format!("{group} ({count})")
+            };
+            list.add_item(label, Some(group));
+        }
+        let index = list
+            .iter()
+            .position(|(_, group)| selected.is_some() && *group == selected);
+        if let Some(index) = index {
+            list.set_selection(index);
+        }
+    });
+    rebuild_cvar_list(siv);
+}
+
+fn shown(name: &str, value: &str, group: Option<&str>, filter: &str) -> bool {
+    let filter = filter.to_lowercase();
+    group.is_none_or(|group| cvar_group(name).eq_ignore_ascii_case(group))
+        && (name.to_lowercase().contains(&filter) || value.to_lowercase().contains(&filter))
+}
+
+/// Redraws the cvars in the selected group that match the filter.
+fn rebuild_cvar_list(siv: &mut Cursive) {
+    let filter = siv
+        .call_on_name("cvar_filter", |v: &mut EditView| v.get_content())
+        .unwrap_or_default();
+    let group = siv
+        .call_on_name("cvar_groups", |v: &mut SelectView<Option<String>>| {
+            v.selection().and_then(|group| (*group).clone())
+        })
+        .flatten();
+    let cvars = current_cvars(siv);
+    let width = cvars.keys().map(String::len).max().unwrap_or(0);
+    siv.call_on_name("cvar_list", |list: &mut SelectView<String>| {
+        let selected = list.selection().map(|name| (*name).clone());
+        list.clear();
+        for (name, value) in &cvars {
+            if shown(name, value, group.as_deref(), &filter) {
+                list.add_item(format!("{name:<width$}  \"{value}\""), name.clone());
+            }
+        }
+        let index = list
+            .iter()
+            .position(|(_, name)| Some(name) == selected.as_ref());
+        if let Some(index) = index {
+            list.set_selection(index);
+        }
+    });
+}
+
+fn edit_cvar(siv: &mut Cursive, name: &str) {
+    let value = current_cvars(siv).get(name).cloned().unwrap_or_default();
+    let set = name.to_string();
+    let save = move |s: &mut Cursive| {
+        let value = s
+            .call_on_name("cvar_value", |v: &mut EditView| v.get_content())
+            .unwrap_or_default();
+        // the console has no way to escape a quote inside a quoted value
+        if value.contains('"') {
+            error_popup("Cvar values can't contain double quotes", s);
+            return;
+        }
+        s.pop_layer();
+        if send_command(s, &commandlist::set_command(&set, &value)) {
+            request_cvars(s);
+        }
+    };
+    let save_on_submit = save.clone();
+    let edit_dialog = Dialog::around(
+        LinearLayout::horizontal()
+            .child(TextView::new("Value: "))
+            .child(
+                EditView::new()
+                    .content(value)
+                    .on_submit(move |s, _| save_on_submit(s))
+                    .with_name("cvar_value")
+                    .min_width(40),
+            ),
+    )
+    .title(format!("Edit {name}"))
+    .dismiss_button("Cancel")
+    .button("Save", save)
+    .min_width(50);
+    siv.add_layer(edit_dialog);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grouping_and_filtering() {
+        let cvars = BTreeMap::from([
+            ("sv_gravity".to_string(), "800".to_string()),
+            ("SV_hostname".to_string(), "Duel".to_string()),
+            ("g_lives".to_string(), "0".to_string()),
+            ("hostname".to_string(), "".to_string()),
+        ]);
+        assert_eq!(
+            groups(&cvars).into_iter().collect::<Vec<_>>(),
+            vec![
+                ("".to_string(), 1),
+                ("g_".to_string(), 1),
+                ("sv_".to_string(), 2)
+            ]
+        );
+        assert!(shown("SV_hostname", "Duel", Some("sv_"), "duel"));
+        assert!(shown("sv_gravity", "800", None, "GRAV"));
+        assert!(!shown("sv_gravity", "800", Some("g_"), ""));
+        assert!(shown("hostname", "", Some(""), ""));
+        assert!(!shown("g_lives", "0", Some(""), ""));
+    }
+}
@@ -0,0 +1,324 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::AppState;
+use super::commands::hide_password;
+use super::error_popup;
+use super::tabs::with_current_tab;
+use cursive::Cursive;
+use cursive::event::{Event, Key};
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::config::{Config, ServerConfig};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Older commands are forgotten past this many.
+const MAX_ENTRIES: usize = 1000;
+
+/// The commands sent to a server, oldest first, with each command only in there once.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<String>,
+    /// Which entry the up and down keys are on, `None` when not browsing
+    position: Option<usize>,
+    /// What was in the input before browsing started, so down can get back to it
+    draft: String,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /// Where a server's history is saved, `$XDG_STATE_HOME/odarcon/history` on Linux.
+    pub fn path_for(server: &ServerConfig) -> Option<PathBuf> {
+        Config::state_dir().map(|dir| {
+            dir.join("history")
+                .join(format!("{}.history", server.file_stem()))
+        })
+    }
+
+    /// Loads the history saved at `path`, a file that doesn't exist yet is an empty history.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let entries = match std::fs::read_to_string(path) {
+            Ok(content) => content.lines().map(str::to_string).collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let mut history = Self {
+            entries: Vec::new(),
+            path: Some(path.to_path_buf()),
+            ..Default::default()
+        };
+        for entry in entries {
+            history.add(entry);
+        }
+        Ok(history)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut content = self.entries.join("\n");
+        content.push('\n');
+        std::fs::write(path, content)
+    }
+
+    fn add(&mut self, command: String) {
+        if command.trim().is_empty() || command.contains('\n') {
+            return;
+        }
+        self.entries.retain(|entry| *entry != command);
+        self.entries.push(command);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+    }
+
+    /// Adds a command that was just sent, moving it to the end if it was already there.
+    pub fn push(&mut self, command: &str) -> io::Result<()> {
+        self.position = None;
+        self.add(command.to_string());
+        self.save()
+    }
+
+    /// The entry before the one being browsed, `current` is what's in the input right now.
+    pub fn previous(&mut self, current: &str) -> Option<&str> {
+        let position = match self.position {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(0) => 0,
+            Some(position) => position - 1,
+        };
+        self.position = Some(position);
+        Some(&self.entries[position])
+    }
+
+    /// The entry after the one being browsed, or the draft after the newest entry.
+    pub fn next(&mut self) -> Option<&str> {
+        let position = self.position?;
+        if position + 1 >= self.entries.len() {
+            self.position = None;
+            return Some(&self.draft);
+        }
+        self.position = Some(position + 1);
+        Some(&self.entries[position + 1])
+    }
+
+    /// Entries containing the query, newest first. Case insensitive.
+    pub fn search(&self, query: &str) -> impl Iterator<Item = &str> {
+        let query = query.to_lowercase();
+        self.entries
+            .iter()
+            .rev()
+            .filter(move |entry| entry.to_lowercase().contains(&query))
+            .map(String::as_str)
+    }
+}
+
+/// Loads the history of the server that's being connected to.
+pub fn load_history(server: &ServerConfig) -> History {
+    match History::path_for(server) {
+        Some(path) => History::load(&path).unwrap_or_else(|e| {
+            log::error!("Command history could not be loaded: {e}");
+            History::default()
+        }),
+        None => History::default(),
+    }
+}
+
+/// Adds a command that was just typed, without the password if it has one.
+pub fn add_to_history(s: &mut Cursive, command: &str) {
+    let servers = s
+        .user_data::<AppState>()
+        .map(|state| state.config.servers.clone())
+        .unwrap_or_default();
+    let command = hide_password(command, &servers);
+    if let Some(Err(e)) = with_current_tab(s, |tab| tab.history.push(&command)) {
+        log::error!("Command history could not be saved: {e}");
+    }
+}
+
+fn set_input(s: &mut Cursive, text: &str) {
+    s.call_on_name("input", |v: &mut EditView| {
+        v.set_content(text);
+    });
+}
+
+/// Replaces the input with the previous (older) or next (newer) history entry.
+pub fn browse(s: &mut Cursive, older: bool) {
+    let current = s
+        .call_on_name("input", |v: &mut EditView| v.get_content())
+        .unwrap_or_default();
+    let entry = with_current_tab(s, |tab| {
+        let entry = if older {
+            tab.history.previous(&current)
+        } else {
+            tab.history.next()
+        };
+        entry.map(str::to_string)
+    })
+    .flatten();
+    if let Some(entry) = entry {
+        set_input(s, &entry);
+    }
+}
+
+/// Opens the reverse search popup, which narrows down the history as you type.
+pub fn reverse_search(s: &mut Cursive) {
+    if with_current_tab(s, |tab| tab.history.entries.is_empty()) != Some(false) {
+        error_popup("There is no command history yet", s);
+        return;
+    }
+    let query = EditView::new()
+        .on_edit(|s, query, _| update_matches(s, query))
+        .on_submit(|s, _| {
+            let selected = s
+                .call_on_name("history_matches", |v: &mut SelectView| v.selection())
+                .flatten();
+            if let Some(selected) = selected {
+                accept(s, &selected);
+            }
+        })
+        .with_name("history_query");
+    let matches = SelectView::<String>::new()
+        .on_submit(|s, entry: &String| accept(s, entry))
+        .with_name("history_matches")
+        .scrollable()
+        .max_height(15);
+    let layout = LinearLayout::vertical()
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new("Search: "))
+                .child(query.full_width()),
+        )
+        .child(DummyView.fixed_height(1))
+        .child(matches);
+    let dialog = OnEventView::new(
+        Dialog::around(layout)
+            .title("Command history")
+            .dismiss_button("Cancel")
+            .min_width(50),
+    )
+    .on_pre_event(Event::CtrlChar('r'), |s| {
+        // like in a shell, pressing it again goes further back
+        s.call_on_name("history_matches", |v: &mut SelectView| v.select_down(1));
+    })
+    .on_pre_event(Key::Esc, |s| {
+        s.pop_layer();
+    });
+    s.add_layer(dialog);
+    update_matches(s, "");
+}
+
+fn update_matches(s: &mut Cursive, query: &str) {
+    let matches: Vec<String> = with_current_tab(s, |tab| {
+        tab.history.search(query).map(str::to_string).collect()
+    })
+    .unwrap_or_default();
+    s.call_on_name("history_matches", |v: &mut SelectView| {
+        v.clear();
+        v.add_all_str(matches);
+    });
+}
+
+fn accept(s: &mut Cursive, entry: &str) {
+    s.pop_layer();
+    set_input(s, entry);
+    if let Ok(cb) = s.focus_name("input") {
+        cb.process(s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(entries: &[&str]) -> History {
+        let mut history = History::default();
+        for entry in entries {
+            history.push(entry).unwrap();
+        }
+        history
+    }
+
+    #[test]
+    fn deduplicates() {
+        let history = history(&["status", "map MAP01", "status", "", "  "]);
+        assert_eq!(history.entries, vec!["map MAP01", "status"]);
+    }
+
+    #[test]
+    fn browsing() {
+        let mut history = history(&["one", "two", "three"]);
+        assert_eq!(history.next(), None);
+        assert_eq!(history.previous("draft"), Some("three"));
+        assert_eq!(history.previous("three"), Some("two"));
+        assert_eq!(history.previous("two"), Some("one"));
+        assert_eq!(history.previous("one"), Some("one"));
+        assert_eq!(history.next(), Some("two"));
+        assert_eq!(history.next(), Some("three"));
+        assert_eq!(history.next(), Some("draft"));
+        assert_eq!(history.next(), None);
+        assert_eq!(History::default().previous(""), None);
+    }
+
+    #[test]
+    fn search_newest_first() {
+        let history = history(&["sv_maxplayers 8", "say hi", "SV_GRAVITY 800"]);
+        let found: Vec<_> = history.search("sv_").collect();
+        assert_eq!(found, vec!["SV_GRAVITY 800", "sv_maxplayers 8"]);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "odarcon-test-history-{}/server.history",
+            std::process::id()
+        ));
+        let mut saved = history(&["one", "two"]);
+        saved.path = Some(path.clone());
+        saved.push("one").unwrap();
+        let loaded = History::load(&path).unwrap();
+        assert_eq!(loaded.entries, vec!["two", "one"]);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn passwords_not_saved() {
+        let path = std::env::temp_dir().join(format!(
+            "odarcon-test-history-password-{}/server.history",
+            std::process::id()
+        ));
+        let mut history = History {
+            path: Some(path.clone()),
+            ..Default::default()
+        };
+        for command in [":connect 10.0.0.1 hunter2", ":open 10.0.0.2:10667 hunter3"] {
+            history.push(&hide_password(command, &[])).unwrap();
+        }
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("hunter"));
+        assert_eq!(
+            saved,
+            ":connect 10.0.0.1 ********\n:open 10.0.0.2:10667 ********\n"
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::tabs::{current_tab_id, with_current_tab, with_tab};
+use super::{error_popup, send_command};
+use cursive::Cursive;
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::protocol::{ClientMessageType, MaplistEntry, ServerMessageType};
+
+/// Opens the maplist browser and asks the server for a fresh copy of the maplist.
+pub fn maplist_layer(siv: &mut Cursive) {
+    let search = EditView::new()
+        .on_edit(|s, _, _| rebuild_maplist(s))
+        .with_name("maplist_search");
+
+    let maps = SelectView::<MaplistEntry>::new()
+        .on_submit(|s, entry: &MaplistEntry| map_actions(s, entry.clone()))
+        .with_name("maplist")
+        .scrollable()
+        .min_height(10);
+
+    let layout = LinearLayout::vertical()
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new("Search: "))
+                .child(search.full_width()),
+        )
+        .child(DummyView.fixed_height(1))
+        .child(Panel::new(maps))
+        .child(TextView::new("Loading maplist...").with_name("maplist_status"));
+
+    siv.add_layer(
+        Dialog::around(layout)
+            .title("Maplist")
+            .button("Refresh", request_maplist)
+            .dismiss_button("Close")
+            .min_width(60),
+    );
+
+    request_maplist(siv);
+}
+
+/// Replaces the tab's stored maplist and redraws the browser if it is open for that tab.
+pub fn update_maplist(siv: &mut Cursive, tab_id: u64, maps: Vec<MaplistEntry>) {
+    let count = maps.len();
+    with_tab(siv, tab_id, |tab| tab.maplist = maps);
+    if current_tab_id(siv) != Some(tab_id) {
+        return;
+    }
+    siv.call_on_name("maplist_status", |v: &mut TextView| {
+        v.set_content(format!("{} maps", count));
+    });
+    rebuild_maplist(siv);
+}
+
+fn request_maplist(siv: &mut Cursive) {
+    let Some((tab_id, Some(connection))) =
+        with_current_tab(siv, |tab| (tab.id, tab.connection.clone()))
+    else {
+        error_popup("Not connected to a server", siv);
+        return;
+    };
+    let cb_sink = siv.cb_sink().clone();
+    tokio::spawn(async move {
+        let reply = connection.request(ClientMessageType::Maplist).await;
+        let _ = cb_sink.send(Box::new(move |s: &mut Cursive| match reply {
+            Ok(reply) => match reply.content {
+                ServerMessageType::Maplist(maps) => update_maplist(s, tab_id, maps),
+                other => {
+                    log::warn!("Unexpected reply to maplist request: {other:?}");
+                    set_status(s, tab_id, "Server sent an unexpected reply");
+                }
+            },
+            Err(e) => set_status(s, tab_id, &format!("Could not load maplist: {}", e)),
+        }));
+    });
+}
+
+/// Shows a status in the maplist, unless the user has switched away from the tab it's about.
+fn set_status(siv: &mut Cursive, tab_id: u64, status: &str) {
+    if current_tab_id(siv) != Some(tab_id) {
+        return;
+    }
+    siv.call_on_name("maplist_status", |v: &mut TextView| {
+        v.set_content(status);
+    });
+}
+
+fn matches_search(entry: &MaplistEntry, search: &str) -> bool {
+    let search = search.to_lowercase();
+    entry.map.to_lowercase().contains(&search)
+        || entry
+            .wads
+            .iter()
+            .any(|wad| wad.to_lowercase().contains(&search))
+}
+
+fn rebuild_maplist(siv: &mut Cursive) {
+    let search = siv
+        .call_on_name("maplist_search", |v: &mut EditView| v.get_content())
+        .unwrap_or_default();
+    let maps: Vec<MaplistEntry> = with_current_tab(siv, |tab| {
+        tab.maplist
+            .iter()
+            .filter(|entry| matches_search(entry, &search))
+            .cloned()
+            .collect()
+    })
+    .unwrap_or_default();
+    siv.call_on_name("maplist", |list: &mut SelectView<MaplistEntry>| {
+        list.clear();
+        for entry in maps {
+            let label = format!(
+                "{:>3}  {:<8}  {}",
+                entry.index,
+                entry.map,
+                entry.wads.join(", ")
+            );
+            list.add_item(label, entry);
+        }
+    });
+}
+
+fn map_actions(siv: &mut Cursive, entry: MaplistEntry) {
+    let goto = entry.index;
+    let next = entry.index;
+    let remove = entry.index;
+    let actions = Dialog::around(
+        LinearLayout::vertical()
+            .child(Button::new("Go to map", move |s| {
+                s.pop_layer();
+                send_command(s, &format!("gotomap {}", goto));
+            }))
+            .child(Button::new("Play next", move |s| {
+                s.pop_layer();
+                send_command(s, &format!("setnextmap {}", next));
+            }))
+            .child(Button::new("Remove", move |s| {
+                let areyousure = Dialog::text("Are you sure you want to remove this map?")
+                    .title("Remove Map")
+                    .dismiss_button("No")
+                    .button("Yes", move |s| {
+                        s.pop_layer();
+                        s.pop_layer();
+                        send_command(s, &format!("delmap {}", remove));
+                        request_maplist(s);
+                    })
+                    .padding_top(1);
+                s.add_layer(areyousure);
+            })),
+    )
+    .dismiss_button("Cancel")
+    .title(format!("{} ({})", entry.map, entry.index))
+    .padding_top(1);
+    siv.add_layer(actions);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_matches_map_and_wads() {
+        let entry = MaplistEntry {
+            index: 3,
+            map: "MAP07".to_string(),
+            wads: vec!["odamex.wad".to_string(), "DOOM2.WAD".to_string()],
+        };
+        assert!(matches_search(&entry, "map07"));
+        assert!(matches_search(&entry, "doom2"));
+        assert!(matches_search(&entry, ""));
+        assert!(!matches_search(&entry, "E1M1"));
+    }
+}
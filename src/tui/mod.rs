@@ -0,0 +1,968 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use cursive::align::HAlign;
+use cursive::event::{Event, Key};
+use cursive::theme;
+use cursive::theme::{ColorStyle, ColorType, Effect, Effects, PaletteColor, Style};
+use cursive::utils::markup::StyledString;
+use cursive::view::*;
+use cursive::views::*;
+use cursive::views::{EditView, LinearLayout, TextView};
+use cursive::{Cursive, CursiveExt};
+use odarcon::client::{CloseReason, ConnectionEvent, ConnectionState, RCONSocket};
+use odarcon::config::{Config, ServerConfig, TlsConfig};
+use odarcon::protocol::{ClientMessageType, PrintLevel};
+use odarcon::sessionlog::SessionLog;
+use odarcon::{config, tls};
+use std::path::PathBuf;
+
+mod aliases;
+mod bans;
+mod broadcast;
+mod commands;
+mod completion;
+mod console;
+mod cvars;
+mod history;
+mod maplist;
+mod players;
+mod scoreboard;
+mod search;
+mod status;
+mod tabs;
+use console::{LineKind, print_to_console, print_to_tab};
+use tabs::Tab;
+use tokio::sync::broadcast::error::RecvError;
+
+// TODO: write the log output to a file in Config::state_dir as well, history and session
+// logs go there already but log messages only reach the debug console
+// TODO: leave main menu layer at the bottom instead of popping it
+// just make sure that the quick connect input fields get cleared
+// this will make it so that the other layers dont need to worry
+// about passing arguments to main_menu
+
+struct AppState {
+    config: Config,
+    tabs: Vec<Tab>,
+    /// Index of the tab that's shown
+    current: usize,
+    next_tab_id: u64,
+}
+
+impl AppState {
+    fn tab(&self) -> Option<&Tab> {
+        self.tabs.get(self.current)
+    }
+
+    fn tab_mut(&mut self) -> Option<&mut Tab> {
+        self.tabs.get_mut(self.current)
+    }
+}
+
+/// Runs the interactive console until the user quits.
+pub fn run() {
+    cursive::logger::init();
+    cursive::logger::set_internal_filter_level(log::LevelFilter::Off);
+
+    let mut siv = Cursive::default();
+
+    #[cfg(debug_assertions)]
+    // hot reload default theme for testing
+    siv.add_global_callback(Event::CtrlChar('r'), |s| {
+        if let Err(e) = s.load_theme_file("./res/theme.toml") {
+            match e {
+                theme::Error::Io(io_error) => {
+                    error_popup("theme.toml could not be loaded", s);
+                    log::error!("theme.toml could not be loaded: {io_error}");
+                }
+                theme::Error::Parse(parse_error) => {
+                    error_popup("theme.toml could not be parsed", s);
+                    log::error!("theme.toml could not be parsed: {parse_error}");
+                }
+            }
+        }
+    });
+
+    #[cfg(debug_assertions)]
+    siv.add_global_callback(Event::CtrlChar('e'), |s| {
+        error_popup("This is a test error popup", s)
+    });
+
+    #[cfg(debug_assertions)]
+    siv.add_global_callback(Event::CtrlChar('d'), |s| {
+        s.toggle_debug_console();
+    });
+
+    let config = Config::load().unwrap_or_else(|e| {
+        // TODO: make the popup more informative
+        error_popup("Config file could not be loaded", &mut siv);
+        log::error!("Config file could not be loaded: {e}");
+        Config::default()
+    });
+
+    siv.set_user_data(AppState {
+        config,
+        tabs: Vec::new(),
+        current: 0,
+        next_tab_id: 0,
+    });
+
+    main_menu(&mut siv);
+
+    if let Some(themefile) = Config::config_dir().map(|dir| dir.join("theme.toml"))
+        && themefile.exists()
+    {
+        if let Err(e) = siv.load_theme_file(themefile) {
+            // TODO: make the popup more informative
+            error_popup("Theme file could not be loaded", &mut siv);
+            match e {
+                theme::Error::Io(io_error) => {
+                    log::error!("Theme file could not be loaded: {io_error}");
+                }
+                theme::Error::Parse(parse_error) => {
+                    log::error!("Theme file could not be parsed: {parse_error}");
+                }
+            }
+        };
+    } else {
+        siv.load_toml(include_str!("../../res/theme.toml")).unwrap();
+    }
+
+    siv.run();
+}
+
+fn error_popup(message: &str, s: &mut Cursive) {
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(StyledString::styled(
+                    "Error:",
+                    Style {
+                        color: ColorStyle::front(ColorType::Palette(PaletteColor::TitlePrimary)),
+                        effects: Effects::only(Effect::Bold),
+                    },
+                )))
+                .child(DummyView.fixed_height(1))
+                .child(PaddedView::new(
+                    Margins {
+                        left: 2,
+                        right: 2,
+                        top: 0,
+                        bottom: 0,
+                    },
+                    TextView::new(message).h_align(HAlign::Center),
+                )),
+        )
+        .dismiss_button("Ok"),
+    );
+}
+
+fn filter_port(name: &str, siv: &mut Cursive, content: &str) {
+    let filtered: String = content.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    let filtered = if filtered.len() > 5 {
+        &filtered[..5]
+    } else {
+        &filtered
+    };
+
+    if filtered != content {
+        siv.call_on_name(name, |v: &mut EditView| {
+            v.set_content(filtered);
+        });
+    }
+}
+
+fn verify_port(port: &str, siv: &mut Cursive) -> Option<u16> {
+    if port.is_empty() {
+        return Some(11666);
+    }
+
+    match port.parse::<u16>() {
+        Ok(port) => Some(port),
+        Err(_) => {
+            error_popup("Port must be in the range 0-65535", siv);
+            None
+        }
+    }
+}
+
+fn main_menu(siv: &mut Cursive) {
+    let mut quick_connect = ListView::new();
+    quick_connect.add_child("Hostname:", EditView::new().with_name("hostname"));
+    quick_connect.add_child(
+        "Port (optional):",
+        EditView::new()
+            .on_edit(|s, content, _| filter_port("port", s, content))
+            .with_name("port"),
+    );
+    quick_connect.add_child("Password:", EditView::new().secret().with_name("password"));
+
+    let quick_connect = Panel::new(PaddedView::new(
+        Margins {
+            left: 3,
+            right: 3,
+            top: 1,
+            bottom: 1,
+        },
+        LinearLayout::vertical()
+            .child(quick_connect)
+            .child(DummyView.fixed_height(1))
+            .child(Button::new("Connect", |s| {
+                let hostname = s.call_on_name("hostname", |v: &mut EditView| v.get_content());
+                let port = s.call_on_name("port", |v: &mut EditView| v.get_content());
+                let password = s.call_on_name("password", |v: &mut EditView| v.get_content());
+                if let Some(port) = verify_port(&port.unwrap(), s) {
+                    let server = ServerConfig {
+                        host: hostname.unwrap().to_string(),
+                        port,
+                        password: password.unwrap().to_string(),
+                        ..Default::default()
+                    };
+                    s.pop_layer();
+                    rcon_layer(s, &server);
+                }
+            })),
+    ))
+    .title("Quick Connect");
+
+    let mut welcome = StyledString::new();
+    welcome.append_plain("Welcome to\n");
+    welcome.append_plain("Oda");
+    welcome.append_styled(
+        "RCON",
+        Style {
+            color: ColorStyle {
+                front: ColorType::Palette(PaletteColor::TitlePrimary),
+                back: ColorType::InheritParent,
+            },
+            effects: Effects::only(Effect::Bold),
+        },
+    );
+    welcome.append_plain("!");
+
+    let welcome = Panel::new(
+        LinearLayout::vertical()
+            .child(DummyView.fixed_height(1))
+            .child(TextView::new(welcome).h_align(HAlign::Center))
+            .child(DummyView.fixed_height(1))
+            .child(Button::new("Settings", |s| {
+                settings(s);
+            }))
+            .child(Button::new("Broadcast", broadcast::broadcast_dialog))
+            .child(Button::new("About", |s| {
+                s.add_layer(
+                    Dialog::info(format!(
+                        "\nOdaRCON {}\nCopyright © 2026 smth idk\nLicensed under the GPLv2+",
+                        env!("CARGO_PKG_VERSION")
+                    ))
+                    .title("About")
+                    .h_align(HAlign::Center),
+                )
+            })),
+    );
+
+    let servers = Panel::new(server_list(siv)).title("Servers");
+
+    siv.add_fullscreen_layer(
+        LinearLayout::vertical()
+            .child(
+                LinearLayout::horizontal()
+                    // TODO: make these widths look better
+                    .child(quick_connect.full_width())
+                    // TODO: i want min_width to be 20 and be used for smaller screens
+                    // but right now the full_width on quick_connect
+                    // just makes this always use it's min width
+                    .child(welcome.min_width(32).max_width(32)),
+            )
+            .child(servers.full_height()),
+    );
+}
+
+fn rebuild_server_list(siv: &mut Cursive) {
+    let server_names: Vec<String> = siv
+        .user_data::<AppState>()
+        .unwrap()
+        .config
+        .servers
+        .iter()
+        .map(|s| s.name.clone())
+        .collect();
+
+    siv.call_on_name("server_list", |list: &mut SelectView<usize>| {
+        list.clear();
+        for (i, name) in server_names.iter().enumerate() {
+            list.add_item(name, i);
+        }
+    });
+}
+
+fn server_list(siv: &mut Cursive) -> impl cursive::View {
+    let mut servers = SelectView::new();
+    siv.with_user_data(|state: &mut AppState| {
+        for (i, server) in state.config.servers.iter().enumerate() {
+            servers.add_item(&server.name, i);
+        }
+    });
+    servers.set_on_submit(|s, server_id| {
+        let server = s
+            // TODO: can we do this without cloning?
+            .with_user_data(|state: &mut AppState| state.config.servers[*server_id].clone())
+            .unwrap();
+        let server_id = *server_id;
+        let title = server.name.clone();
+        let modes = Dialog::around(
+            LinearLayout::vertical()
+                .child(Button::new("Connect", move |s| {
+                    s.pop_layer();
+                    rcon_layer(s, &server);
+                }))
+                .child(Button::new("Edit", move |s| {
+                    s.pop_layer(); // todo: maybe only pop this after choosing save in the edit dialog?
+                    edit_server(s, "Edit Server", Some(server_id));
+                }))
+                .child(Button::new("Delete", move |s| {
+                    let areyousure = Dialog::text("Are you sure you want to delete this server?")
+                        .title("Delete Server")
+                        .dismiss_button("No")
+                        .button("Yes", move |s| {
+                            // TODO: figure out difference between this and with_user_data
+                            let config = &mut s.user_data::<AppState>().unwrap().config;
+                            config.servers.remove(server_id);
+                            if let Err(e) = config.save() {
+                                // TODO: make the popup more informative
+                                error_popup("Config file could not be saved", s);
+                                log::error!("Config file could not be saved: {e}");
+                            }
+                            s.pop_layer();
+                            s.pop_layer();
+                            s.call_on_name("server_list", |list: &mut SelectView| {
+                                // todo dont just unwrap
+                                list.remove_item(server_id);
+                            });
+                            rebuild_server_list(s);
+                        })
+                        .padding_top(1);
+                    s.add_layer(areyousure);
+                })),
+        )
+        .dismiss_button("Cancel")
+        .title(title)
+        .padding_top(1);
+        s.add_layer(modes);
+    });
+    let servers = Panel::new(servers.with_name("server_list").scrollable());
+    LinearLayout::vertical()
+        .child(
+            LinearLayout::horizontal()
+                .child(DummyView.fixed_width(1))
+                .child(Button::new("New Server", |s| {
+                    edit_server(s, "New Server", None);
+                })),
+        )
+        .child(servers)
+}
+
+fn settings(siv: &mut Cursive) {
+    let config = &siv.user_data::<AppState>().unwrap().config; // TODO: no unwrap pls, maybe move to its own get_config function
+    let mut settings = ListView::new();
+    settings.add_child(
+        "Colorize server log",
+        Checkbox::new()
+            .with_checked(config.colorize_logs)
+            .with_name("colorize_logs"),
+    );
+    settings.add_child(
+        "Save session logs",
+        Checkbox::new()
+            .with_checked(config.logging.enabled)
+            .with_name("session_logs"),
+    );
+    siv.add_layer(
+        Dialog::around(settings)
+            .padding_top(1)
+            .title("Settings")
+            .dismiss_button("Cancel")
+            .button("Save", |s| {
+                let colorize = s
+                    .call_on_name("colorize_logs", |v: &mut Checkbox| v.is_checked())
+                    .unwrap();
+                let session_logs = s
+                    .call_on_name("session_logs", |v: &mut Checkbox| v.is_checked())
+                    .unwrap();
+                if let Some(Err(e)) = s.with_user_data(|state: &mut AppState| {
+                    state.config.colorize_logs = colorize;
+                    state.config.logging.enabled = session_logs;
+                    state.config.save()
+                }) {
+                    // TODO: make the popup more informative
+                    error_popup("Config file could not be saved", s);
+                    log::error!("Config file could not be saved: {e}");
+                } else {
+                    s.pop_layer();
+                }
+                console::rerender(s);
+            }),
+    );
+}
+
+fn edit_server(siv: &mut Cursive, title: &str, server_index: Option<usize>) {
+    let init = match server_index {
+        Some(index) => siv.user_data::<AppState>().unwrap().config.servers[index].clone(),
+        None => ServerConfig::default(),
+    };
+    // leave the port empty for new servers so the default comes from verify_port
+    let init_port = match server_index {
+        Some(_) => init.port.to_string(),
+        None => "".to_string(),
+    };
+    let init_pass = init.password.clone();
+    let init_tls = init.tls.clone().unwrap_or_default();
+    // aliases can only be edited in the config file, keep them as they are
+    let init_aliases = init.aliases.clone();
+
+    let mut server_settings = ListView::new();
+    server_settings.add_child(
+        "Name:",
+        EditView::new().content(init.name).with_name("server_name"),
+    );
+    server_settings.add_child(
+        "Hostname:",
+        EditView::new()
+            .content(init.host)
+            .with_name("server_hostname"),
+    );
+    server_settings.add_child(
+        "Port (optional):",
+        EditView::new()
+            .content(init_port)
+            .on_edit(|s, content, _| filter_port("server_port", s, content))
+            .with_name("server_port"),
+    );
+    server_settings.add_child(
+        "Password:",
+        EditView::new().secret().with_name("server_password"),
+    );
+    // TODO: get the labels from to_string or something on the versions
+    let mut protocol_versions = SelectView::new().popup().with_all(vec![
+        ("Latest (1.0.0)", config::ProtocolVersion::Latest),
+        (
+            "1.0.0",
+            config::ProtocolVersion::Custom {
+                major: 1,
+                minor: 0,
+                revision: 0,
+            },
+        ),
+    ]);
+    let proto_index = protocol_versions
+        .iter()
+        .position(|(_, proto)| *proto == init.protoversion);
+    if let Some(proto_index) = proto_index {
+        protocol_versions.set_selection(proto_index);
+    }
+    server_settings.add_child(
+        "Protocol Version:",
+        protocol_versions.with_name("protocol_version"),
+    );
+    server_settings.add_child(
+        "Reconnect automatically:",
+        Checkbox::new()
+            .with_checked(init.reconnect)
+            .with_name("server_reconnect"),
+    );
+    server_settings.add_child(
+        "Tags (comma separated):",
+        EditView::new()
+            .content(init.tags.join(", "))
+            .with_name("server_tags"),
+    );
+    server_settings.add_child(
+        "Use TLS (wss://):",
+        Checkbox::new()
+            .with_checked(init.tls.is_some())
+            .with_name("server_tls"),
+    );
+    server_settings.add_child(
+        "CA file (optional):",
+        EditView::new()
+            .content(
+                init_tls
+                    .ca_file
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+            )
+            .with_name("server_ca_file"),
+    );
+    server_settings.add_child(
+        "Cert fingerprint (optional):",
+        EditView::new()
+            .content(init_tls.fingerprint.unwrap_or_default())
+            .with_name("server_fingerprint"),
+    );
+
+    let edit_dialog = Dialog::around(server_settings)
+        .title(title)
+        .dismiss_button("Cancel")
+        .button("Save", move |s| {
+            let name = s.call_on_name("server_name", |v: &mut EditView| v.get_content());
+            let hostname = s.call_on_name("server_hostname", |v: &mut EditView| v.get_content());
+            let port = s.call_on_name("server_port", |v: &mut EditView| v.get_content());
+            let password = s
+                .call_on_name("server_password", |v: &mut EditView| v.get_content())
+                // TODO: don't just unwrap here
+                .unwrap();
+            let password = if password.is_empty() {
+                init_pass.clone()
+            } else {
+                password.to_string()
+            };
+            let protocol = s.call_on_name(
+                "protocol_version",
+                |v: &mut SelectView<config::ProtocolVersion>| {
+                    let id = v.selected_id();
+                    match id {
+                        Some(id) => *v.get_item(id).unwrap().1,
+                        None => config::ProtocolVersion::Latest,
+                    }
+                },
+            );
+            let reconnect = s.call_on_name("server_reconnect", |v: &mut Checkbox| v.is_checked());
+            let tags = s
+                .call_on_name("server_tags", |v: &mut EditView| {
+                    parse_tags(&v.get_content())
+                })
+                .unwrap_or_default();
+            let Some(tls) = read_tls_settings(s) else {
+                return;
+            };
+            if let Some(port) = verify_port(&port.unwrap(), s) {
+                let server = ServerConfig {
+                    // TODO: dont just do unwraps
+                    name: name.unwrap().to_string(),
+                    host: hostname.unwrap().to_string(),
+                    port,
+                    password,
+                    protoversion: protocol.unwrap(),
+                    reconnect: reconnect.unwrap(),
+                    tls,
+                    tags,
+                    aliases: init_aliases.clone(),
+                };
+                if let Some(Err(e)) = s.with_user_data(|state: &mut AppState| {
+                    // TODO: make sure the main server list gets updated
+                    match server_index {
+                        Some(index) => state.config.servers[index] = server,
+                        None => state.config.add_server(server),
+                    }
+                    state.config.save()
+                }) {
+                    // TODO: make the popup more informative
+                    error_popup("Config file could not be saved", s);
+                    log::error!("Config file could not be saved: {e}");
+                } else {
+                    s.pop_layer();
+                }
+                rebuild_server_list(s);
+            }
+        })
+        .min_width(56);
+    siv.add_layer(edit_dialog);
+}
+
+/// Splits the tags field at commas, dropping empty tags.
+fn parse_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reads the TLS fields of the edit server dialog, showing an error popup if they're invalid.
+/// The outer `None` means the dialog shouldn't be saved.
+fn read_tls_settings(s: &mut Cursive) -> Option<Option<TlsConfig>> {
+    let enabled = s
+        .call_on_name("server_tls", |v: &mut Checkbox| v.is_checked())
+        .unwrap_or(false);
+    if !enabled {
+        return Some(None);
+    }
+    let ca_file = s
+        .call_on_name("server_ca_file", |v: &mut EditView| v.get_content())
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    let fingerprint = s
+        .call_on_name("server_fingerprint", |v: &mut EditView| v.get_content())
+        .map(|fingerprint| fingerprint.trim().to_string())
+        .filter(|fingerprint| !fingerprint.is_empty());
+    if let Some(fingerprint) = &fingerprint
+        && tls::parse_fingerprint(fingerprint).is_err()
+    {
+        error_popup(
+            "Certificate fingerprint must be a SHA-256 hash\n(64 hex digits, colons are optional)",
+            s,
+        );
+        return None;
+    }
+    Some(Some(TlsConfig {
+        ca_file: ca_file.map(PathBuf::from),
+        fingerprint,
+    }))
+}
+
+fn rcon_layer(siv: &mut Cursive, server: &ServerConfig) {
+    let output = TextView::new("")
+        .with_name("output")
+        .scrollable()
+        .scroll_strategy(ScrollStrategy::StickToBottom)
+        .with_name("output_scroll");
+    let output_panel = Panel::new(output)
+        .title("Console")
+        .with_name("console_panel");
+
+    let input = EditView::new()
+        .on_submit(|s, text| {
+            let servers = s
+                .user_data::<AppState>()
+                .map(|state| state.config.servers.clone())
+                .unwrap_or_default();
+            print_to_console(
+                s,
+                LineKind::Command,
+                &commands::hide_password(text, &servers),
+            );
+
+            s.call_on_name("input", |v: &mut EditView| {
+                v.set_content("");
+            });
+
+            history::add_to_history(s, text);
+            if let Some(command) = text.strip_prefix(':') {
+                commands::run(s, command);
+            } else if !aliases::run_alias(s, text) && send_command(s, text) {
+                tabs::with_current_tab(s, |tab| tab.completions.note_command(text));
+            }
+        })
+        .filler(" ")
+        .with_name("input");
+    let input = OnEventView::new(input)
+        .on_pre_event(Key::Tab, completion::complete)
+        .on_pre_event(Key::Up, |s| history::browse(s, true))
+        .on_pre_event(Key::Down, |s| history::browse(s, false))
+        .on_pre_event(Event::CtrlChar('r'), history::reverse_search);
+
+    let input_row = LinearLayout::horizontal()
+        .child(TextView::new("> "))
+        .child(input.full_width());
+
+    let input_panel = Panel::new(input_row)
+        .title("Command (Tab: complete, Up/Down: history, Ctrl-R: search, :help)");
+
+    let tab_bar = TextView::new("").no_wrap().with_name("tab_bar");
+
+    let left_pane = LinearLayout::vertical()
+        .child(tab_bar)
+        .child(status::status_header())
+        .child(output_panel.full_height())
+        .child(search::search_bar())
+        .child(input_panel)
+        .with_name("left");
+
+    let right_pane = LinearLayout::vertical()
+        .child(Button::new("Maplist", maplist::maplist_layer).with_name("button1"))
+        .child(Button::new("Filters", console::filters_dialog))
+        .child(Button::new("Players", players::focus_players))
+        .child(Button::new("Bans", bans::bans_layer))
+        .child(Button::new("Cvars", cvars::cvars_layer))
+        .child(Button::new("Scores", scoreboard::scoreboard_layer))
+        .child(Button::new("New Tab", tabs::new_tab_dialog))
+        .child(Button::new("Broadcast", broadcast::broadcast_dialog))
+        .child(DummyView.fixed_height(1))
+        .child(Button::new("Disconnect", tabs::close_current_tab))
+        .child(Button::new("Quit", |s| s.quit()));
+
+    let right_panel = LinearLayout::vertical()
+        .child(Panel::new(right_pane).title("Actions"))
+        .child(players::players_panel().full_height())
+        .fixed_width(24);
+
+    let console_view = LinearLayout::horizontal()
+        .child(left_pane)
+        .child(right_panel);
+
+    // fn update_left_max_width(s: &mut Cursive) {
+    //     let term_width = s.screen_size().x;
+    //     let right_width = 18;
+    //     if term_width > right_width {
+    //         let max_left = term_width - right_width;
+    //         s.call_on_name("left", |v: &mut ResizedView<LinearLayout>| {
+    //             v.set_width(SizeConstraint::AtMost(max_left));
+    //         });
+    //         s.call_on_name("output", |v: &mut TextView| {
+    //             v.append(format!("> new width: {}\n", max_left));
+    //         });
+    //     }
+    // }
+
+    // siv.add_global_callback(cursive::event::Event::WindowResize, |s| {
+    //     update_left_max_width(s);
+    // });
+
+    // siv.add_global_callback(cursive::event::Event::Refresh, |s| {
+    //     // This runs after the first frame
+    //     let term_width = s.screen_size().x;
+    //     let right_width = 18;
+    //     let max_left = term_width.saturating_sub(right_width);
+    //     s.call_on_name("left", |v: &mut ResizedView<LinearLayout>| {
+    //         v.set_width(SizeConstraint::AtMost(max_left));
+    //     });
+    //     s.call_on_name("output", |v: &mut TextView| {
+    //         v.append(format!("> new width: {}\n", max_left));
+    //     });
+
+    //     // remove this callback after first run
+    //     s.clear_global_callbacks(cursive::event::Event::Refresh);
+    // });
+    //
+    let mut layer = OnEventView::new(console_view)
+        .on_event('/', |s| match s.focus_name("input") {
+            Ok(cb) => cb.process(s),
+            Err(_) => error_popup("Console input could not be focused", s),
+        })
+        .on_event(Key::Esc, |s| match s.focus_name("button1") {
+            Ok(cb) => cb.process(s),
+            Err(_) => error_popup("Button 1 could not be focused", s),
+        });
+    layer.set_on_pre_event(Event::CtrlChar('f'), search::open_search);
+    layer.set_on_pre_event(Event::CtrlChar('t'), tabs::new_tab_dialog);
+    layer.set_on_pre_event(Event::Alt(Key::Right), tabs::next_tab);
+    layer.set_on_pre_event(Event::Alt(Key::Left), tabs::previous_tab);
+    layer.set_on_pre_event(Event::Ctrl(Key::PageDown), tabs::next_tab);
+    layer.set_on_pre_event(Event::Ctrl(Key::PageUp), tabs::previous_tab);
+    for (event, kind) in console::filter_shortcuts() {
+        layer.set_on_event(event, move |s| console::toggle_filter(s, kind.clone()));
+    }
+
+    siv.add_fullscreen_layer(layer);
+    tabs::open_tab(siv, server);
+}
+
+/// Connects a tab that was just opened to its server, forwarding the connection's
+/// events to it.
+fn connect_tab(s: &mut Cursive, tab_id: u64) {
+    let Some(server) = tabs::with_tab(s, tab_id, |tab| tab.server.clone()) else {
+        return;
+    };
+    match RCONSocket::connect(&server) {
+        Ok(connection) => {
+            let mut events = connection.subscribe();
+            let cb_sink = s.cb_sink().clone();
+            tokio::spawn(async move {
+                loop {
+                    let event = match events.recv().await {
+                        Ok(event) => event,
+                        Err(RecvError::Lagged(skipped)) => {
+                            log::warn!("Console fell behind, skipped {skipped} events");
+                            continue;
+                        }
+                        Err(RecvError::Closed) => break,
+                    };
+                    let login_failed = matches!(
+                        event,
+                        ConnectionEvent::Closed {
+                            reason: CloseReason::LoginFailed(_)
+                        }
+                    );
+                    let sent = cb_sink.send(Box::new(move |s: &mut Cursive| {
+                        if tabs::with_tab(s, tab_id, |_| ()).is_some() {
+                            handle_connection_event(s, tab_id, event);
+                        }
+                    }));
+                    if sent.is_err() || login_failed {
+                        break;
+                    }
+                }
+            });
+            players::watch_players(s, tab_id, connection.clone());
+            status::watch_latency(s, tab_id, connection.clone());
+            tabs::with_tab(s, tab_id, |tab| tab.connection = Some(connection));
+            open_session_log(s, tab_id);
+        }
+        Err(err) => {
+            log::error!("Failed to connect to server: {err}");
+            tabs::close_tab(s, tab_id);
+            error_popup(&format!("Failed to connect to server:\n{}", err), s);
+        }
+    }
+}
+
+/// Sends a console command to the server of the tab that's shown, or complains in the
+/// console if there's no connection.
+fn send_command(s: &mut Cursive, text: &str) -> bool {
+    match tabs::current_tab_id(s) {
+        Some(tab_id) => send_to_tab(s, tab_id, text),
+        None => false,
+    }
+}
+
+/// Sends a console command to a tab's server, or complains in that tab if there's
+/// no connection.
+fn send_to_tab(s: &mut Cursive, tab_id: u64, text: &str) -> bool {
+    let command = ClientMessageType::Command(text.to_string());
+    let sent = tabs::with_tab(s, tab_id, |tab| match &tab.connection {
+        Some(connection) if *connection.state().borrow() != ConnectionState::Closed => {
+            connection.send(command);
+            true
+        }
+        _ => false,
+    });
+    if sent != Some(true) {
+        print_to_tab(s, tab_id, LineKind::Client, "Not connected to a server\n");
+        return false;
+    }
+    write_session_log(s, tab_id, |log| log.command(text));
+    true
+}
+
+fn open_session_log(s: &mut Cursive, tab_id: u64) {
+    let opened = s.with_user_data(|state: &mut AppState| -> std::io::Result<()> {
+        let Some(tab) = state.tabs.iter_mut().find(|tab| tab.id == tab_id) else {
+            return Ok(());
+        };
+        tab.session_log = None;
+        if !state.config.logging.enabled {
+            return Ok(());
+        }
+        tab.session_log = Some(SessionLog::open(&tab.server, &state.config.logging)?);
+        Ok(())
+    });
+    if let Some(Err(e)) = opened {
+        log::error!("Session log could not be opened: {e}");
+        print_to_tab(
+            s,
+            tab_id,
+            LineKind::Client,
+            &format!("Session log could not be opened: {e}\n"),
+        );
+    }
+}
+
+/// Writes to a tab's session log, and stops logging if that fails so that a full disk
+/// doesn't fill the console with errors.
+fn write_session_log(
+    s: &mut Cursive,
+    tab_id: u64,
+    write: impl FnOnce(&mut SessionLog) -> std::io::Result<()>,
+) {
+    let failed = tabs::with_tab(s, tab_id, |tab| {
+        let error = write(tab.session_log.as_mut()?).err()?;
+        tab.session_log = None;
+        Some(error)
+    });
+    if let Some(Some(e)) = failed {
+        log::error!("Session log could not be written: {e}");
+        print_to_tab(
+            s,
+            tab_id,
+            LineKind::Client,
+            &format!("Session log could not be written, logging stopped: {e}\n"),
+        );
+    }
+}
+
+fn handle_connection_event(s: &mut Cursive, tab_id: u64, event: ConnectionEvent) {
+    write_session_log(s, tab_id, |log| log.event(&event));
+    match event {
+        ConnectionEvent::Connecting => print_to_tab(s, tab_id, LineKind::Client, "Connecting...\n"),
+        ConnectionEvent::Connected => {
+            print_to_tab(s, tab_id, LineKind::Client, "Connected to odamex server!\n")
+        }
+        ConnectionEvent::Authenticated => {
+            print_to_tab(s, tab_id, LineKind::Client, "Successfully logged in\n");
+            completion::refresh_completions(s, tab_id);
+        }
+        ConnectionEvent::AuthFailed(reason) => print_to_tab(
+            s,
+            tab_id,
+            LineKind::Client,
+            &format!("Login failed: {}\n", reason),
+        ),
+        ConnectionEvent::Print { level, text } => {
+            if level == PrintLevel::Obituary {
+                scoreboard::record_obituaries(s, tab_id, &text);
+            }
+            print_to_tab(s, tab_id, LineKind::Server(level), &text)
+        }
+        ConnectionEvent::ServerInfo(info) => {
+            print_to_tab(
+                s,
+                tab_id,
+                LineKind::Client,
+                &format!(
+                    "{} is playing {} on {}\n",
+                    info.hostname, info.gametype, info.map
+                ),
+            );
+            status::set_server_info(s, tab_id, info);
+        }
+        ConnectionEvent::Maplist(maps) => {
+            print_to_tab(
+                s,
+                tab_id,
+                LineKind::Client,
+                &format!("Maplist updated ({} maps)\n", maps.len()),
+            );
+            maplist::update_maplist(s, tab_id, maps);
+        }
+        ConnectionEvent::ProtocolError { raw, error } => print_to_tab(
+            s,
+            tab_id,
+            LineKind::Client,
+            &format!("Received invalid message: {}\n{}\n", raw, error),
+        ),
+        ConnectionEvent::SendError(error) => {
+            print_to_tab(s, tab_id, LineKind::Client, &format!("{}\n", error))
+        }
+        ConnectionEvent::Closed {
+            reason: CloseReason::LoginFailed(reason),
+        } => {
+            let title = tabs::with_tab(s, tab_id, |tab| tab.title()).unwrap_or_default();
+            tabs::close_tab(s, tab_id);
+            error_popup(&format!("Login to {title} failed: {reason}"), s);
+        }
+        ConnectionEvent::Reconnecting {
+            attempt,
+            delay,
+            reason,
+        } => {
+            print_to_tab(
+                s,
+                tab_id,
+                LineKind::Client,
+                &format!(
+                    "{}, reconnecting in {}s (attempt {})\n",
+                    reason,
+                    delay.as_secs(),
+                    attempt
+                ),
+            );
+            status::connection_lost(s, tab_id);
+        }
+        ConnectionEvent::Closed { reason } => {
+            print_to_tab(s, tab_id, LineKind::Client, &format!("{}\n", reason))
+        }
+    }
+}
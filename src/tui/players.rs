@@ -0,0 +1,252 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::status;
+use super::tabs::{current_tab_id, with_current_tab, with_tab};
+use super::{error_popup, send_command};
+use cursive::Cursive;
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::client::{ConnectionState, RCONError, RCONSocket};
+use odarcon::players::{self, Player, PlayerAction};
+use std::time::Duration;
+
+/// How often the player list is asked for while logged in.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+type PlayerPanel = Panel<ScrollView<NamedView<SelectView<Player>>>>;
+
+/// The roster shown under the actions, filled in by [`watch_players`].
+pub fn players_panel() -> impl View {
+    let list = SelectView::<Player>::new()
+        .on_submit(|s, player: &Player| player_actions(s, player.clone()))
+        .with_name("player_list")
+        .scrollable();
+    Panel::new(list).title("Players").with_name("players_panel")
+}
+
+/// Moves the focus to the player list, if there's anyone on it.
+pub fn focus_players(s: &mut Cursive) {
+    let empty =
+        with_current_tab(s, |tab| tab.players.as_ref().is_none_or(Vec::is_empty)).unwrap_or(true);
+    if empty {
+        error_popup("There are no players on the server", s);
+        return;
+    }
+    if let Ok(cb) = s.focus_name("player_list") {
+        cb.process(s);
+    }
+}
+
+/// Keeps a tab's player list up to date for as long as its connection is open. The list
+/// is fetched every time the connection logs in and then every [`REFRESH_INTERVAL`].
+pub fn watch_players(s: &mut Cursive, tab_id: u64, connection: RCONSocket) {
+    let cb_sink = s.cb_sink().clone();
+    tokio::spawn(async move {
+        let mut state = connection.state();
+        loop {
+            let logged_in = state
+                .wait_for(|state| {
+                    matches!(
+                        state,
+                        ConnectionState::LoggedIn
+                            | ConnectionState::Closed
+                            | ConnectionState::LoginFailed(_)
+                    )
+                })
+                .await
+                .is_ok_and(|state| *state == ConnectionState::LoggedIn);
+            if !logged_in {
+                break;
+            }
+            let players = players::fetch(&connection).await;
+            let sent = cb_sink.send(Box::new(move |s: &mut Cursive| {
+                update_players(s, tab_id, players)
+            }));
+            if sent.is_err() {
+                break;
+            }
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
+
+/// Asks for the current tab's players now, rather than waiting for the next refresh.
+fn refresh_players(s: &mut Cursive) {
+    let Some((tab_id, Some(connection))) =
+        with_current_tab(s, |tab| (tab.id, tab.connection.clone()))
+    else {
+        return;
+    };
+    let cb_sink = s.cb_sink().clone();
+    tokio::spawn(async move {
+        let players = players::fetch(&connection).await;
+        let _ = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            update_players(s, tab_id, players)
+        }));
+    });
+}
+
+/// Replaces the tab's player list and redraws it if the tab is shown. A failed fetch
+/// keeps the old list, the next refresh will try again.
+fn update_players(s: &mut Cursive, tab_id: u64, players: Result<Vec<Player>, RCONError>) {
+    let players = match players {
+        Ok(players) => players,
+        Err(e) => {
+            log::warn!("Could not fetch the player list: {e}");
+            return;
+        }
+    };
+    with_tab(s, tab_id, |tab| tab.players = Some(players));
+    if current_tab_id(s) == Some(tab_id) {
+        rebuild_player_list(s);
+        status::update_header(s);
+    }
+}
+
+fn player_label(player: &Player) -> String {
+    format!("{:>4}  {}", player.frags, player.plain_name())
+}
+
+/// Redraws the player list from the current tab, keeping the selected player selected.
+pub fn rebuild_player_list(s: &mut Cursive) {
+    let players = with_current_tab(s, |tab| tab.players.clone())
+        .flatten()
+        .unwrap_or_default();
+    s.call_on_name("player_list", |list: &mut SelectView<Player>| {
+        let selected = list.selection().map(|player| player.id);
+        list.clear();
+        for player in &players {
+            list.add_item(player_label(player), player.clone());
+        }
+        if let Some(index) = players
+            .iter()
+            .position(|player| Some(player.id) == selected)
+        {
+            list.set_selection(index);
+        }
+    });
+    s.call_on_name("players_panel", |v: &mut PlayerPanel| {
+        v.set_title(format!("Players ({})", players.len()))
+    });
+}
+
+/// Sends the action for the player and fetches the list again to show what it did.
+fn act(s: &mut Cursive, id: u32, action: PlayerAction) {
+    if send_command(s, &action.command(id)) {
+        refresh_players(s);
+    }
+}
+
+/// Asks for the text an action needs. `fields` are the labels of the inputs, whose
+/// contents are passed to `done` in the same order.
+fn input_dialog(
+    s: &mut Cursive,
+    title: &str,
+    fields: &[&str],
+    done: impl Fn(&mut Cursive, Vec<String>) + Send + Sync + 'static,
+) {
+    let names: Vec<String> = (0..fields.len())
+        .map(|i| format!("player_input_{i}"))
+        .collect();
+    let mut list = ListView::new();
+    for (field, name) in fields.iter().zip(&names) {
+        list.add_child(*field, EditView::new().with_name(name).min_width(30));
+    }
+    s.add_layer(
+        Dialog::around(list)
+            .title(title)
+            .dismiss_button("Cancel")
+            .button("OK", move |s| {
+                let values = names
+                    .iter()
+                    .map(|name| {
+                        s.call_on_name(name, |v: &mut EditView| v.get_content().to_string())
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                s.pop_layer();
+                s.pop_layer();
+                done(s, values);
+            }),
+    );
+}
+
+fn player_actions(s: &mut Cursive, player: Player) {
+    let id = player.id;
+    let name = player.plain_name();
+    let info = format!(
+        "Address: {}\nFrags: {}\nPing: {}",
+        player.address, player.frags, player.ping
+    );
+    let message_title = format!("Message {name}");
+    let kick_title = format!("Kick {name}");
+    let ban_title = format!("Ban {name}");
+    let actions = Dialog::around(
+        LinearLayout::vertical()
+            .child(TextView::new(info))
+            .child(DummyView.fixed_height(1))
+            .child(Button::new("Message", move |s| {
+                input_dialog(s, &message_title, &["Message:"], move |s, values| {
+                    let message = values[0].trim();
+                    if !message.is_empty() {
+                        act(s, id, PlayerAction::Message(message.to_string()));
+                    }
+                })
+            }))
+            .child(Button::new("Force spectate", move |s| {
+                s.pop_layer();
+                act(s, id, PlayerAction::ForceSpectate);
+            }))
+            .child(Button::new("Kick", move |s| {
+                input_dialog(s, &kick_title, &["Reason:"], move |s, values| {
+                    let reason = values[0].trim().to_string();
+                    act(s, id, PlayerAction::Kick { reason });
+                })
+            }))
+            .child(Button::new("Ban", move |s| {
+                input_dialog(
+                    s,
+                    &ban_title,
+                    &["Length (empty for good):", "Reason:"],
+                    move |s, values| {
+                        let length = values[0].trim().to_string();
+                        let reason = values[1].trim().to_string();
+                        act(s, id, PlayerAction::Ban { length, reason });
+                    },
+                )
+            })),
+    )
+    .dismiss_button("Cancel")
+    .title(format!("{name} ({id})"))
+    .padding_top(1);
+    s.add_layer(actions);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels() {
+        let player = Player {
+            id: 3,
+            name: "\\cdPlayer".to_string(),
+            address: "127.0.0.1:10666".to_string(),
+            frags: -2,
+            ping: 40,
+        };
+        assert_eq!(player_label(&player), "  -2  Player");
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::commands::expand_home;
+use super::error_popup;
+use super::tabs::{current_tab_id, with_current_tab, with_tab};
+use cursive::Cursive;
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::obituary;
+use odarcon::scoreboard::{Scoreboard, StatsFormat, Tally};
+
+/// Opens the frags and deaths counted on the current tab since it was opened.
+pub fn scoreboard_layer(s: &mut Cursive) {
+    let scores = TextView::new("")
+        .no_wrap()
+        .with_name("score_list")
+        .scrollable()
+        .min_height(10);
+
+    let layout = LinearLayout::vertical()
+        .child(TextView::new("").no_wrap().with_name("score_header"))
+        .child(Panel::new(scores))
+        .child(TextView::new("").with_name("score_status"));
+
+    s.add_layer(
+        Dialog::around(layout)
+            .title("Scoreboard")
+            .button("Export CSV", |s| export_dialog(s, StatsFormat::Csv))
+            .button("Export JSON", |s| export_dialog(s, StatsFormat::Json))
+            .button("Reset", reset)
+            .dismiss_button("Close")
+            .min_width(60),
+    );
+
+    rebuild_scoreboard(s);
+}
+
+/// Counts the obituaries in something the tab's server printed.
+pub fn record_obituaries(s: &mut Cursive, tab_id: u64, text: &str) {
+    let obituaries = obituary::parse_obituaries(text);
+    if obituaries.is_empty() {
+        return;
+    }
+    with_tab(s, tab_id, |tab| {
+        for obituary in &obituaries {
+            tab.scoreboard.record(obituary);
+        }
+    });
+    if current_tab_id(s) == Some(tab_id) {
+        rebuild_scoreboard(s);
+    }
+}
+
+/// The header and the rows of the scoreboard, with the names as wide as the longest.
+fn score_lines(standings: &[Tally]) -> (String, String) {
+    let width = standings
+        .iter()
+        .map(|tally| tally.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let header = format!(
+        "{:<width$}  {:>5}  {:>5}  {:>6}  {:>8}",
+        "Name", "Frags", "Kills", "Deaths", "Suicides"
+    );
+    let rows = standings
+        .iter()
+        .map(|tally| {
+            format!(
+                "{:<width$}  {:>5}  {:>5}  {:>6}  {:>8}\n",
+                tally.name, tally.frags, tally.kills, tally.deaths, tally.suicides
+            )
+        })
+        .collect();
+    (header, rows)
+}
+
+/// Redraws the scoreboard from the current tab, if it's open.
+fn rebuild_scoreboard(s: &mut Cursive) {
+    let Some(scoreboard) = with_current_tab(s, |tab| tab.scoreboard.clone()) else {
+        return;
+    };
+    let (header, rows) = score_lines(&scoreboard.standings());
+    // the rows are indented by the panel's border
+    s.call_on_name("score_header", |v: &mut TextView| {
+        v.set_content(format!(" {header}"))
+    });
+    s.call_on_name("score_list", |v: &mut TextView| v.set_content(rows));
+    let status = match scoreboard.obituaries() {
+        0 => "No obituaries yet this session".to_string(),
+        1 => "1 obituary this session".to_string(),
+        count => format!("{count} obituaries this session"),
+    };
+    s.call_on_name("score_status", |v: &mut TextView| v.set_content(status));
+}
+
+fn reset(s: &mut Cursive) {
+    let areyousure = Dialog::text("Start counting frags and deaths from zero?")
+        .title("Reset Scoreboard")
+        .dismiss_button("No")
+        .button("Yes", |s| {
+            s.pop_layer();
+            with_current_tab(s, |tab| tab.scoreboard = Scoreboard::default());
+            rebuild_scoreboard(s);
+        })
+        .padding_top(1);
+    s.add_layer(areyousure);
+}
+
+/// Asks where to write the scoreboard, suggesting a file named after the server.
+fn export_dialog(s: &mut Cursive, format: StatsFormat) {
+    let Some(stem) = with_current_tab(s, |tab| tab.server.file_stem()) else {
+        return;
+    };
+    let path = format!("~/{stem}-stats.{}", format.extension());
+    let export = move |s: &mut Cursive| {
+        let path = s
+            .call_on_name("stats_path", |v: &mut EditView| v.get_content())
+            .unwrap_or_default();
+        let path = expand_home(path.trim());
+        let exported = with_current_tab(s, |tab| tab.scoreboard.export(&path, format));
+        match exported {
+            Some(Ok(())) => {
+                s.pop_layer();
+                s.add_layer(Dialog::info(format!(
+                    "Saved the scoreboard to {}",
+                    path.display()
+                )));
+            }
+            Some(Err(e)) => error_popup(&format!("Could not save to {}: {e}", path.display()), s),
+            None => {}
+        }
+    };
+    let export_on_submit = export;
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::horizontal()
+                .child(TextView::new("File: "))
+                .child(
+                    EditView::new()
+                        .content(path)
+                        .on_submit(move |s, _| export_on_submit(s))
+                        .with_name("stats_path")
+                        .min_width(40),
+                ),
+        )
+        .title(format!("Export {}", format.extension().to_uppercase()))
+        .dismiss_button("Cancel")
+        .button("Save", export),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let standings = [Tally {
+            name: "Player".to_string(),
+            frags: -1,
+            kills: 0,
+            deaths: 1,
+            suicides: 1,
+        }];
+        assert_eq!(
+            score_lines(&standings),
+            (
+                "Name    Frags  Kills  Deaths  Suicides".to_string(),
+                "Player     -1      0       1         1\n".to_string()
+            )
+        );
+    }
+}
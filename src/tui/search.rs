@@ -0,0 +1,358 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::console;
+use super::tabs::with_current_tab;
+use cursive::Cursive;
+use cursive::event::Key;
+use cursive::theme::{ColorStyle, ColorType, Effect, PaletteColor};
+use cursive::utils::lines::simple::LinesIterator;
+use cursive::utils::markup::StyledString;
+use cursive::view::*;
+use cursive::views::*;
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
+
+/// An active search over the console scrollback.
+#[derive(Debug)]
+pub struct Search {
+    regex: Regex,
+    pub only_matching: bool,
+    /// Where each match starts in the rendered console text, oldest first
+    pub matches: Vec<usize>,
+    /// The match that's been jumped to, `None` means the newest one
+    pub current: Option<usize>,
+}
+
+impl Search {
+    /// Builds a search for the query, which is matched literally unless `use_regex` is set.
+    /// It's case insensitive unless the query has uppercase letters in it.
+    pub fn new(query: &str, use_regex: bool, only_matching: bool) -> Result<Self, regex::Error> {
+        let pattern = if use_regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!query.chars().any(char::is_uppercase))
+            .build()?;
+        Ok(Self {
+            regex,
+            only_matching,
+            matches: Vec::new(),
+            current: None,
+        })
+    }
+
+    pub fn find(&self, text: &str) -> Vec<Range<usize>> {
+        self.regex
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect()
+    }
+
+    /// The index of the match that's been jumped to, if there are any matches.
+    pub fn current_index(&self) -> Option<usize> {
+        match self.current {
+            _ if self.matches.is_empty() => None,
+            Some(current) => Some(current.min(self.matches.len() - 1)),
+            None => Some(self.matches.len() - 1),
+        }
+    }
+
+    fn step(&mut self, forward: bool) {
+        let Some(current) = self.current_index() else {
+            return;
+        };
+        let count = self.matches.len();
+        self.current = Some(if forward {
+            (current + 1) % count
+        } else {
+            (current + count - 1) % count
+        });
+    }
+}
+
+/// Restyles the matched ranges of a line, keeping the rest of its styling.
+/// `current` is the index into `ranges` of the match that's been jumped to.
+pub fn highlight(
+    line: &StyledString,
+    ranges: &[Range<usize>],
+    current: Option<usize>,
+) -> StyledString {
+    if ranges.is_empty() {
+        return line.clone();
+    }
+    let mut highlighted = StyledString::new();
+    let mut offset = 0;
+    for span in line.spans() {
+        let span_range = offset..offset + span.content.len();
+        let mut position = span_range.start;
+        for (i, range) in ranges.iter().enumerate() {
+            let start = range.start.clamp(position, span_range.end);
+            let end = range.end.clamp(position, span_range.end);
+            if start == end {
+                continue;
+            }
+            let text = &line.source()[position..start];
+            highlighted.append_styled(text, *span.attr);
+            let style = if current == Some(i) {
+                ColorStyle::highlight()
+            } else {
+                ColorStyle::new(
+                    ColorType::Palette(PaletteColor::Primary),
+                    ColorType::Palette(PaletteColor::HighlightInactive),
+                )
+            };
+            highlighted.append_styled(
+                &line.source()[start..end],
+                span.attr.combine(style).combine(Effect::Bold),
+            );
+            position = end;
+        }
+        highlighted.append_styled(&line.source()[position..span_range.end], *span.attr);
+        offset = span_range.end;
+    }
+    highlighted
+}
+
+/// Shows the search bar under the console.
+pub fn open_search(s: &mut Cursive) {
+    s.call_on_name("search_bar", |v: &mut HideableView<LinearLayout>| {
+        v.unhide()
+    });
+    if let Ok(cb) = s.focus_name("search_input") {
+        cb.process(s);
+    }
+    update_search(s);
+}
+
+fn close_search(s: &mut Cursive) {
+    s.call_on_name("search_bar", |v: &mut HideableView<LinearLayout>| v.hide());
+    with_current_tab(s, |tab| tab.console.search = None);
+    console::rerender(s);
+    console::scroll_to_bottom(s);
+    if let Ok(cb) = s.focus_name("input") {
+        cb.process(s);
+    }
+}
+
+/// Hides the search bar and empties it, for when another tab is shown.
+pub fn hide_search_bar(s: &mut Cursive) {
+    s.call_on_name("search_bar", |v: &mut HideableView<LinearLayout>| v.hide());
+    s.call_on_name("search_input", |v: &mut EditView| {
+        v.set_content("");
+    });
+    set_status(s, "");
+}
+
+/// Rebuilds the search from the search bar, after the query or the options changed.
+fn update_search(s: &mut Cursive) {
+    let query = s
+        .call_on_name("search_input", |v: &mut EditView| v.get_content())
+        .unwrap_or_default();
+    let use_regex = s
+        .call_on_name("search_regex", |v: &mut Checkbox| v.is_checked())
+        .unwrap_or(false);
+    let only_matching = s
+        .call_on_name("search_only_matching", |v: &mut Checkbox| v.is_checked())
+        .unwrap_or(false);
+    let search = match query.as_str() {
+        "" => Ok(None),
+        query => Search::new(query, use_regex, only_matching).map(Some),
+    };
+    let error = search.as_ref().err().map(|e| e.to_string());
+    with_current_tab(s, |tab| tab.console.search = search.ok().flatten());
+    console::rerender(s);
+    match error {
+        Some(_) => set_status(s, "Invalid regex"),
+        None => jump_to_current(s),
+    }
+}
+
+/// Moves to the next (newer) or previous (older) match.
+pub fn step(s: &mut Cursive, forward: bool) {
+    let stepped = with_current_tab(s, |tab| match &mut tab.console.search {
+        Some(search) => {
+            search.step(forward);
+            true
+        }
+        None => false,
+    });
+    if stepped == Some(true) {
+        console::rerender(s);
+        jump_to_current(s);
+    }
+}
+
+fn set_status(s: &mut Cursive, status: &str) {
+    s.call_on_name("search_status", |v: &mut TextView| v.set_content(status));
+}
+
+/// Scrolls the console so the current match is in view, and updates the match counter.
+fn jump_to_current(s: &mut Cursive) {
+    let current = with_current_tab(s, |tab| {
+        let search = tab.console.search.as_ref()?;
+        Some((search.current_index(), search.matches.len()))
+    })
+    .flatten();
+    let (offset, status) = match current {
+        None => (None, String::new()),
+        Some((None, _)) => (None, "No matches".to_string()),
+        Some((Some(index), count)) => {
+            let offset = with_current_tab(s, |tab| {
+                tab.console
+                    .search
+                    .as_ref()
+                    .map(|search| search.matches[index])
+            })
+            .flatten();
+            (offset, format!("{}/{}", index + 1, count))
+        }
+    };
+    set_status(s, &status);
+    let Some(offset) = offset else {
+        return;
+    };
+    s.call_on_name(
+        "output_scroll",
+        |v: &mut ScrollView<NamedView<TextView>>| {
+            let viewport = v.content_viewport();
+            let content = v.get_inner_mut().get_mut().get_shared_content();
+            let content = content.get_content();
+            let row = LinesIterator::new(content.source(), viewport.width().max(1))
+                .position(|row| row.end > offset)
+                .unwrap_or(0);
+            v.set_scroll_strategy(ScrollStrategy::KeepRow);
+            v.set_offset((0, row.saturating_sub(viewport.height() / 2)));
+        },
+    );
+}
+
+/// The search bar, hidden until Ctrl-F is pressed.
+pub fn search_bar() -> impl View {
+    let bar = LinearLayout::horizontal()
+        .child(TextView::new("Find: "))
+        .child(
+            EditView::new()
+                .on_edit(|s, _, _| update_search(s))
+                .on_submit(|s, _| step(s, false))
+                .filler(" ")
+                .with_name("search_input")
+                .full_width(),
+        )
+        .child(TextView::new(" "))
+        .child(
+            Checkbox::new()
+                .on_change(|s, _| update_search(s))
+                .with_name("search_regex"),
+        )
+        .child(TextView::new(" Regex "))
+        .child(
+            Checkbox::new()
+                .on_change(|s, _| update_search(s))
+                .with_name("search_only_matching"),
+        )
+        .child(TextView::new(" Only matching "))
+        .child(TextView::new("").with_name("search_status").min_width(10));
+    let bar = OnEventView::new(bar)
+        .on_pre_event(Key::Esc, close_search)
+        .on_pre_event(Key::Up, |s| step(s, false))
+        .on_pre_event(Key::Down, |s| step(s, true));
+    HideableView::new(
+        LinearLayout::vertical()
+            .child(Panel::new(bar).title("Search (Enter/Up: older, Down: newer, Esc: close)")),
+    )
+    .hidden()
+    .with_name("search_bar")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cursive::theme::Style;
+
+    #[test]
+    fn literal_and_regex() {
+        let literal = Search::new("a.c", false, false).unwrap();
+        assert_eq!(literal.find("abc a.c"), vec![4..7]);
+        let regex = Search::new("a.c", true, false).unwrap();
+        assert_eq!(regex.find("abc a.c"), vec![0..3, 4..7]);
+        assert!(Search::new("(", true, false).is_err());
+        assert!(Search::new("(", false, false).is_ok());
+    }
+
+    #[test]
+    fn smart_case() {
+        assert_eq!(
+            Search::new("player", false, false)
+                .unwrap()
+                .find("Player")
+                .len(),
+            1
+        );
+        assert!(
+            Search::new("Player", false, false)
+                .unwrap()
+                .find("player")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn stepping_wraps_around() {
+        let mut search = Search::new("x", false, false).unwrap();
+        assert_eq!(search.current_index(), None);
+        search.matches = vec![0, 10, 20];
+        assert_eq!(search.current_index(), Some(2));
+        search.step(true);
+        assert_eq!(search.current_index(), Some(0));
+        search.step(false);
+        search.step(false);
+        assert_eq!(search.current_index(), Some(1));
+    }
+
+    #[test]
+    fn highlight_keeps_text_and_styles() {
+        let mut line = StyledString::plain("Player ");
+        line.append_styled("fragged", Effect::Italic);
+        line.append_plain(" Player2\n");
+        let highlighted = highlight(&line, &[0..6, 11..22], Some(1));
+        assert_eq!(highlighted.source(), line.source());
+        let italic = Style::from(Effect::Italic).effects.statuses[Effect::Italic];
+        let spans: Vec<_> = highlighted
+            .spans()
+            .filter(|span| !span.content.is_empty())
+            .map(|span| {
+                (
+                    span.content,
+                    span.attr.effects.statuses[Effect::Italic] == italic,
+                    span.attr.color == Style::from(ColorStyle::highlight()).color,
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("Player", false, false),
+                (" ", false, false),
+                ("frag", true, false),
+                ("ged", true, true),
+                (" Player2", false, true),
+                ("\n", false, false),
+            ]
+        );
+    }
+}
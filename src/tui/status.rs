@@ -0,0 +1,208 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::tabs::{Tab, current_tab_id, with_current_tab, with_tab};
+use cursive::Cursive;
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::client::{ConnectionState, RCONError, RCONSocket};
+use odarcon::protocol::ServerInfo;
+use odarcon::status::{self, Ping, Status};
+use std::time::{Duration, Instant};
+
+/// How often the server is pinged while logged in.
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// What a tab knows about its server besides the player list and cvars.
+#[derive(Debug, Default)]
+pub struct ServerStatus {
+    /// What the server said about itself when logging in, and when that was
+    info: Option<(ServerInfo, Instant)>,
+    /// The last ping, `None` while there's no connection to ping over
+    ping: Option<Ping>,
+}
+
+/// The line over the console that says what the server is doing.
+pub fn status_header() -> impl View {
+    TextView::new("").no_wrap().with_name("status_header")
+}
+
+/// Keeps the tab's latency up to date for as long as its connection is open, the same
+/// way [`watch_players`](super::players::watch_players) keeps its player list.
+pub fn watch_latency(s: &mut Cursive, tab_id: u64, connection: RCONSocket) {
+    let cb_sink = s.cb_sink().clone();
+    tokio::spawn(async move {
+        let mut state = connection.state();
+        loop {
+            let logged_in = state
+                .wait_for(|state| {
+                    matches!(
+                        state,
+                        ConnectionState::LoggedIn
+                            | ConnectionState::Closed
+                            | ConnectionState::LoginFailed(_)
+                    )
+                })
+                .await
+                .is_ok_and(|state| *state == ConnectionState::LoggedIn);
+            if !logged_in {
+                break;
+            }
+            let ping = status::ping(&connection).await;
+            let sent = cb_sink.send(Box::new(move |s: &mut Cursive| {
+                update_ping(s, tab_id, ping)
+            }));
+            if sent.is_err() {
+                break;
+            }
+            tokio::time::sleep(PING_INTERVAL).await;
+        }
+    });
+}
+
+fn update_ping(s: &mut Cursive, tab_id: u64, ping: Result<Ping, RCONError>) {
+    let ping = ping
+        .inspect_err(|e| log::warn!("Could not ping the server: {e}"))
+        .ok();
+    with_tab(s, tab_id, |tab| tab.status.ping = ping);
+    if current_tab_id(s) == Some(tab_id) {
+        update_header(s);
+    }
+}
+
+/// Stores what the server said about itself when the tab logged in.
+pub fn set_server_info(s: &mut Cursive, tab_id: u64, info: ServerInfo) {
+    with_tab(s, tab_id, |tab| {
+        tab.status.info = Some((info, Instant::now()))
+    });
+    if current_tab_id(s) == Some(tab_id) {
+        update_header(s);
+    }
+}
+
+/// Forgets the latency of a tab that lost its connection.
+pub fn connection_lost(s: &mut Cursive, tab_id: u64) {
+    with_tab(s, tab_id, |tab| tab.status.ping = None);
+    if current_tab_id(s) == Some(tab_id) {
+        update_header(s);
+    }
+}
+
+/// Puts together the status of a tab's server. What's read over the connection now
+/// wins over what the server said when logging in, since cvars can change after that.
+fn tab_status(tab: &Tab) -> Status {
+    let info = tab.status.info.as_ref();
+    let cvar = |name: &str| tab.completions.list.cvars.get(name).cloned();
+    let hostname = tab
+        .status
+        .ping
+        .as_ref()
+        .and_then(|ping| ping.hostname.clone())
+        .or_else(|| cvar("sv_hostname"))
+        .or_else(|| info.map(|(info, _)| info.hostname.clone()));
+    let gametype = cvar("sv_gametype")
+        .and_then(|value| status::gametype_name(&value))
+        .map(str::to_string)
+        .or_else(|| info.map(|(info, _)| info.gametype.clone()));
+    let maxplayers = cvar("sv_maxplayers")
+        .and_then(|value| value.parse().ok())
+        .or_else(|| info.map(|(info, _)| info.maxplayers));
+    Status {
+        hostname: hostname.filter(|hostname| !hostname.is_empty()),
+        map: tab
+            .status
+            .ping
+            .as_ref()
+            .and_then(|ping| ping.map.clone())
+            .or_else(|| info.map(|(info, _)| info.map.clone())),
+        gametype,
+        players: tab
+            .connection
+            .as_ref()
+            .and(tab.players.as_ref())
+            .map(Vec::len),
+        maxplayers,
+        uptime: info.map(|(info, received)| Duration::from_secs(info.uptime) + received.elapsed()),
+        latency: tab.status.ping.as_ref().map(|ping| ping.latency),
+    }
+}
+
+/// Redraws the header from the current tab.
+pub fn update_header(s: &mut Cursive) {
+    let status = with_current_tab(s, |tab| tab_status(tab).to_string()).unwrap_or_default();
+    s.call_on_name("status_header", |v: &mut TextView| v.set_content(status));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odarcon::config::ServerConfig;
+
+    #[test]
+    fn live_values_win() {
+        let mut tab = Tab::new(0, &ServerConfig::default());
+        assert_eq!(tab_status(&tab), Status::default());
+
+        let info = ServerInfo {
+            hostname: "Duel".to_string(),
+            map: "MAP07".to_string(),
+            gametype: "Deathmatch".to_string(),
+            maxplayers: 2,
+            uptime: 600,
+        };
+        tab.status.info = Some((info, Instant::now()));
+        tab.completions
+            .list
+            .cvars
+            .insert("sv_maxplayers".to_string(), "16".to_string());
+        tab.status.ping = Some(Ping {
+            latency: Duration::from_millis(20),
+            hostname: Some("Renamed".to_string()),
+            map: None,
+        });
+        assert_eq!(
+            tab_status(&tab).to_string(),
+            "Renamed | MAP07 | Deathmatch | ?/16 players | up 10m | 20 ms"
+        );
+
+        // the map changed since logging in
+        tab.status.ping = Some(Ping {
+            latency: Duration::from_millis(20),
+            hostname: None,
+            map: Some("MAP11".to_string()),
+        });
+        assert_eq!(tab_status(&tab).map.as_deref(), Some("MAP11"));
+        tab.status.info = None;
+        assert_eq!(tab_status(&tab).to_string(), "MAP11 | ?/16 players | 20 ms");
+    }
+
+    #[tokio::test]
+    async fn players_unknown_until_fetched() {
+        let server = ServerConfig {
+            host: "127.0.0.1".to_string(),
+            port: 1,
+            ..Default::default()
+        };
+        let mut tab = Tab::new(0, &server);
+        tab.connection = Some(RCONSocket::connect(&server).unwrap());
+        tab.completions
+            .list
+            .cvars
+            .insert("sv_maxplayers".to_string(), "8".to_string());
+        assert_eq!(tab_status(&tab).to_string(), "?/8 players");
+        tab.players = Some(Vec::new());
+        assert_eq!(tab_status(&tab).to_string(), "0/8 players");
+        tab.connection.take().unwrap().disconnect();
+    }
+}
@@ -0,0 +1,364 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use super::completion::{self, Completions};
+use super::console::{self, Console};
+use super::history::{self, History};
+use super::status::{self, ServerStatus};
+use super::{AppState, connect_tab, main_menu, players, search};
+use cursive::Cursive;
+use cursive::theme::{ColorStyle, Effect, PaletteColor, Style};
+use cursive::utils::markup::StyledString;
+use cursive::view::*;
+use cursive::views::*;
+use odarcon::bans::{Ban, BanOrder};
+use odarcon::client::RCONSocket;
+use odarcon::config::ServerConfig;
+use odarcon::players::Player;
+use odarcon::protocol::MaplistEntry;
+use odarcon::scoreboard::Scoreboard;
+use odarcon::sessionlog::SessionLog;
+
+/// A console tab, with its own connection, scrollback and input.
+pub struct Tab {
+    /// Never reused, so events for a tab that was closed can be told apart
+    pub id: u64,
+    pub server: ServerConfig,
+    pub connection: Option<RCONSocket>,
+    pub console: Console,
+    pub session_log: Option<SessionLog>,
+    pub history: History,
+    pub completions: Completions,
+    pub maplist: Vec<MaplistEntry>,
+    /// `None` until the player list was first fetched
+    pub players: Option<Vec<Player>>,
+    pub bans: Vec<Ban>,
+    /// How the ban list is sorted, kept while the tab is open
+    pub ban_order: BanOrder,
+    pub status: ServerStatus,
+    /// Frags and deaths since the tab was opened
+    pub scoreboard: Scoreboard,
+    /// What was typed into the input while another tab was shown
+    pub input: String,
+    /// Whether something was printed while the tab was in the background
+    pub unread: bool,
+}
+
+impl Tab {
+    pub fn new(id: u64, server: &ServerConfig) -> Self {
+        Self {
+            id,
+            server: server.clone(),
+            connection: None,
+            console: Console::default(),
+            session_log: None,
+            history: History::default(),
+            completions: Completions::default(),
+            maplist: Vec::new(),
+            players: None,
+            bans: Vec::new(),
+            ban_order: BanOrder::default(),
+            status: ServerStatus::default(),
+            scoreboard: Scoreboard::default(),
+            input: String::new(),
+            unread: false,
+        }
+    }
+
+    /// The server's name, or its address for quick connects.
+    pub fn title(&self) -> String {
+        if self.server.name.is_empty() {
+            format!("{}:{}", self.server.host, self.server.port)
+        } else {
+            self.server.name.clone()
+        }
+    }
+}
+
+/// Runs `f` on the tab with the given id, if it's still open.
+pub fn with_tab<T>(s: &mut Cursive, id: u64, f: impl FnOnce(&mut Tab) -> T) -> Option<T> {
+    s.user_data::<AppState>()?
+        .tabs
+        .iter_mut()
+        .find(|tab| tab.id == id)
+        .map(f)
+}
+
+/// Runs `f` on the tab that's shown.
+pub fn with_current_tab<T>(s: &mut Cursive, f: impl FnOnce(&mut Tab) -> T) -> Option<T> {
+    s.user_data::<AppState>()?.tab_mut().map(f)
+}
+
+pub fn current_tab_id(s: &mut Cursive) -> Option<u64> {
+    with_current_tab(s, |tab| tab.id)
+}
+
+/// Where the current tab ends up after the tab at `closed` is removed, with `len` tabs left.
+fn index_after_close(current: usize, closed: usize, len: usize) -> usize {
+    if closed < current {
+        current - 1
+    } else {
+        current.min(len.saturating_sub(1))
+    }
+}
+
+/// The tab after or before `current`, wrapping around.
+fn cycle(current: usize, len: usize, forward: bool) -> usize {
+    match (forward, len) {
+        (_, 0) => 0,
+        (true, len) => (current + 1) % len,
+        (false, len) => (current + len - 1) % len,
+    }
+}
+
+fn tab_bar(tabs: &[Tab], current: usize) -> StyledString {
+    let mut bar = StyledString::new();
+    for (i, tab) in tabs.iter().enumerate() {
+        let marker = if tab.unread { "*" } else { "" };
+        let label = format!(" {}: {}{marker} ", i + 1, tab.title());
+        if i == current {
+            bar.append_styled(label, ColorStyle::highlight());
+        } else if tab.unread {
+            bar.append_styled(
+                label,
+                Style::from(ColorStyle::front(PaletteColor::TitlePrimary)).combine(Effect::Bold),
+            );
+        } else {
+            bar.append_plain(label);
+        }
+    }
+    bar
+}
+
+/// Redraws the tab bar above the console.
+pub fn update_tab_bar(s: &mut Cursive) {
+    let Some(bar) = s
+        .user_data::<AppState>()
+        .map(|state| tab_bar(&state.tabs, state.current))
+    else {
+        return;
+    };
+    s.call_on_name("tab_bar", |v: &mut TextView| v.set_content(bar));
+}
+
+/// Puts the current tab's console and input on screen.
+fn show_current(s: &mut Cursive) {
+    let Some(input) = with_current_tab(s, |tab| {
+        tab.unread = false;
+        tab.input.clone()
+    }) else {
+        return;
+    };
+    s.call_on_name("input", |v: &mut EditView| {
+        v.set_content(input);
+    });
+    search::hide_search_bar(s);
+    console::rerender(s);
+    console::scroll_to_bottom(s);
+    players::rebuild_player_list(s);
+    status::update_header(s);
+    update_tab_bar(s);
+    if let Ok(cb) = s.focus_name("input") {
+        cb.process(s);
+    }
+}
+
+/// Shows the tab at `index`, keeping what was typed in the one that's left.
+pub fn switch_to(s: &mut Cursive, index: usize) {
+    let input = s
+        .call_on_name("input", |v: &mut EditView| v.get_content())
+        .unwrap_or_default();
+    let switched = s.with_user_data(|state: &mut AppState| {
+        if index >= state.tabs.len() {
+            return false;
+        }
+        if let Some(tab) = state.tab_mut() {
+            tab.input = input.to_string();
+            tab.console.search = None;
+        }
+        state.current = index;
+        true
+    });
+    if switched == Some(true) {
+        show_current(s);
+    }
+}
+
+pub fn next_tab(s: &mut Cursive) {
+    cycle_tabs(s, true);
+}
+
+pub fn previous_tab(s: &mut Cursive) {
+    cycle_tabs(s, false);
+}
+
+fn cycle_tabs(s: &mut Cursive, forward: bool) {
+    let Some(index) = s
+        .user_data::<AppState>()
+        .map(|state| cycle(state.current, state.tabs.len(), forward))
+    else {
+        return;
+    };
+    switch_to(s, index);
+}
+
+/// Adds a tab for the server, either at the end or in place of the current one.
+fn add_tab(s: &mut Cursive, server: &ServerConfig, replace: bool) {
+    let mut tab = Tab::new(0, server);
+    tab.history = history::load_history(server);
+    tab.completions = completion::load_completions(server);
+    let Some((id, index)) = s.with_user_data(|state: &mut AppState| {
+        tab.id = state.next_tab_id;
+        state.next_tab_id += 1;
+        let id = tab.id;
+        match state.tabs.get_mut(state.current) {
+            Some(current) if replace => {
+                if let Some(connection) = current.connection.take() {
+                    connection.disconnect();
+                }
+                *current = tab;
+            }
+            _ => state.tabs.push(tab),
+        }
+        let index = if replace {
+            state.current
+        } else {
+            state.tabs.len() - 1
+        };
+        (id, index)
+    }) else {
+        return;
+    };
+    if replace {
+        // the input goes with the old tab
+        s.call_on_name("input", |v: &mut EditView| {
+            v.set_content("");
+        });
+    }
+    switch_to(s, index);
+    connect_tab(s, id);
+}
+
+/// Opens a new tab connected to the server and switches to it.
+pub fn open_tab(s: &mut Cursive, server: &ServerConfig) {
+    add_tab(s, server, false);
+}
+
+/// Connects the current tab to another server, or the same one again.
+pub fn replace_tab(s: &mut Cursive, server: &ServerConfig) {
+    add_tab(s, server, true);
+}
+
+/// Disconnects and closes a tab. Closing the last one goes back to the main menu.
+pub fn close_tab(s: &mut Cursive, id: u64) {
+    let Some((remaining, was_current)) = s
+        .with_user_data(|state: &mut AppState| {
+            let index = state.tabs.iter().position(|tab| tab.id == id)?;
+            let tab = state.tabs.remove(index);
+            if let Some(connection) = tab.connection {
+                connection.disconnect();
+            }
+            let was_current = index == state.current;
+            state.current = index_after_close(state.current, index, state.tabs.len());
+            Some((state.tabs.len(), was_current))
+        })
+        .flatten()
+    else {
+        return;
+    };
+    if remaining == 0 {
+        s.pop_layer();
+        main_menu(s);
+    } else if was_current {
+        show_current(s);
+    } else {
+        update_tab_bar(s);
+    }
+}
+
+pub fn close_current_tab(s: &mut Cursive) {
+    if let Some(id) = current_tab_id(s) {
+        close_tab(s, id);
+    }
+}
+
+/// Lists the saved servers to open in a new tab.
+pub fn new_tab_dialog(s: &mut Cursive) {
+    let Some(servers) = s
+        .user_data::<AppState>()
+        .map(|state| state.config.servers.clone())
+    else {
+        return;
+    };
+    let mut list = SelectView::new().on_submit(|s, server: &ServerConfig| {
+        s.pop_layer();
+        open_tab(s, server);
+    });
+    for server in servers {
+        list.add_item(server.name.clone(), server);
+    }
+    let layout = LinearLayout::vertical()
+        .child(list.scrollable().max_height(15))
+        .child(DummyView.fixed_height(1))
+        .child(TextView::new(
+            "Other servers can be opened with\n:open host[:port] [password]",
+        ));
+    s.add_layer(
+        Dialog::around(layout)
+            .title("New Tab")
+            .dismiss_button("Cancel")
+            .min_width(40),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(name: &str, unread: bool) -> Tab {
+        let server = ServerConfig {
+            name: name.to_string(),
+            host: "10.0.0.1".to_string(),
+            ..Default::default()
+        };
+        Tab {
+            unread,
+            ..Tab::new(0, &server)
+        }
+    }
+
+    #[test]
+    fn tab_labels() {
+        let tabs = [tab("Duel", false), tab("", true), tab("CTF", true)];
+        assert_eq!(
+            tab_bar(&tabs, 2).source(),
+            " 1: Duel  2: 10.0.0.1:11666*  3: CTF* "
+        );
+    }
+
+    #[test]
+    fn switching_and_closing() {
+        assert_eq!(cycle(0, 3, true), 1);
+        assert_eq!(cycle(2, 3, true), 0);
+        assert_eq!(cycle(0, 3, false), 2);
+        assert_eq!(cycle(0, 0, false), 0);
+        // closing a tab before the current one keeps the same tab shown
+        assert_eq!(index_after_close(2, 0, 2), 1);
+        assert_eq!(index_after_close(1, 2, 2), 1);
+        // closing the current tab shows the next one, or the new last one
+        assert_eq!(index_after_close(1, 1, 2), 1);
+        assert_eq!(index_after_close(2, 2, 2), 1);
+        assert_eq!(index_after_close(0, 0, 0), 0);
+    }
+}
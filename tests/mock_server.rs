@@ -0,0 +1,399 @@
+/*
+ * Copyright (C) 2026  Mia McMahill
+ *
+ * This program is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ */
+
+use odarcon::bans;
+use odarcon::broadcast::BroadcastError;
+use odarcon::client::{ConnectionEvent, ConnectionState, RCONError, RCONSocket};
+use odarcon::commandlist::CommandList;
+use odarcon::config::ServerConfig;
+use odarcon::mock::{DROP_COMMAND, MockConfig, MockServer};
+use odarcon::obituary::Obituary;
+use odarcon::players::{self, PlayerAction};
+use odarcon::protocol::{ClientMessageType, PrintLevel, ServerMessageType};
+use odarcon::status;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn start(password: &str) -> MockServer {
+    let config = MockConfig {
+        password: password.to_string(),
+        ..Default::default()
+    };
+    MockServer::start("127.0.0.1:0", config).await.unwrap()
+}
+
+fn server_config(server: &MockServer, password: &str, reconnect: bool) -> ServerConfig {
+    ServerConfig {
+        name: "mock".to_string(),
+        host: "127.0.0.1".to_string(),
+        port: server.port(),
+        password: password.to_string(),
+        reconnect,
+        ..Default::default()
+    }
+}
+
+async fn next_event(events: &mut broadcast::Receiver<ConnectionEvent>) -> ConnectionEvent {
+    tokio::time::timeout(TIMEOUT, events.recv())
+        .await
+        .expect("timed out waiting for an event")
+        .unwrap()
+}
+
+async fn wait_for_state(socket: &RCONSocket, wanted: ConnectionState) {
+    let mut state = socket.state();
+    tokio::time::timeout(TIMEOUT, state.wait_for(|state| *state == wanted))
+        .await
+        .expect("timed out waiting for the connection state")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn scripted_prints_cover_every_level() {
+    let server = start("secret").await;
+    let socket = RCONSocket::connect(&server_config(&server, "secret", false)).unwrap();
+    let mut events = socket.subscribe();
+
+    let mut levels = vec![];
+    while levels.len() < MockConfig::default().script.len() {
+        if let ConnectionEvent::Print { level, .. } = next_event(&mut events).await {
+            levels.push(level);
+        }
+    }
+    assert_eq!(
+        levels,
+        vec![
+            PrintLevel::Pickup,
+            PrintLevel::Obituary,
+            PrintLevel::High,
+            PrintLevel::Chat,
+            PrintLevel::TeamChat,
+            PrintLevel::ServerChat,
+            PrintLevel::Warning,
+            PrintLevel::Error,
+        ]
+    );
+    socket.disconnect();
+}
+
+#[tokio::test]
+async fn scripted_obituaries_parse() {
+    let server = start("").await;
+    let socket = RCONSocket::connect(&server_config(&server, "", false)).unwrap();
+    let mut events = socket.subscribe();
+
+    let text = loop {
+        if let ConnectionEvent::Print {
+            level: PrintLevel::Obituary,
+            text,
+        } = next_event(&mut events).await
+        {
+            break text;
+        }
+    };
+    let obituary = Obituary::parse(&text).unwrap();
+    assert_eq!(obituary.victim, "Player2");
+    assert_eq!(obituary.killer.as_deref(), Some("Player"));
+    assert!(!obituary.suicide);
+    socket.disconnect();
+}
+
+#[tokio::test]
+async fn wrong_password() {
+    let server = start("secret").await;
+    let socket = RCONSocket::connect(&server_config(&server, "guess", false)).unwrap();
+    wait_for_state(
+        &socket,
+        ConnectionState::LoginFailed("Incorrect password".to_string()),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn commands_are_echoed() {
+    let server = start("").await;
+    let socket = RCONSocket::connect(&server_config(&server, "", false)).unwrap();
+    wait_for_state(&socket, ConnectionState::LoggedIn).await;
+
+    let reply = socket
+        .request(ClientMessageType::Command("say hello".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(
+        reply.content,
+        ServerMessageType::Print {
+            printlevel: PrintLevel::High,
+            text: "say hello\n".to_string(),
+        }
+    );
+    socket.disconnect();
+}
+
+#[tokio::test]
+async fn maplist_and_delmap() {
+    let server = start("").await;
+    let socket = RCONSocket::connect(&server_config(&server, "", false)).unwrap();
+    wait_for_state(&socket, ConnectionState::LoggedIn).await;
+    let mut events = socket.subscribe();
+
+    let reply = socket.request(ClientMessageType::Maplist).await.unwrap();
+    let ServerMessageType::Maplist(maps) = reply.content else {
+        panic!("expected a maplist, got {:?}", reply.content);
+    };
+    assert_eq!(maps.len(), 3);
+    assert_eq!(maps[1].map, "MAP07");
+
+    socket.send(ClientMessageType::Command("delmap 0".to_string()));
+    let maps = loop {
+        if let ConnectionEvent::Maplist(maps) = next_event(&mut events).await {
+            break maps;
+        }
+    };
+    assert_eq!(maps.len(), 2);
+    assert_eq!((maps[0].index, maps[0].map.as_str()), (0, "MAP07"));
+    socket.disconnect();
+}
+
+#[tokio::test]
+async fn command_and_cvar_lists() {
+    let server = start("").await;
+    let socket = RCONSocket::connect(&server_config(&server, "", false)).unwrap();
+    wait_for_state(&socket, ConnectionState::LoggedIn).await;
+
+    let list = CommandList::fetch(&socket).await.unwrap();
+    assert!(list.commands.contains("cvarlist"));
+    assert!(list.commands.contains("kick"));
+    assert!(!list.commands.contains("sv_gravity"));
+    assert_eq!(list.cvars.len(), MockConfig::default().cvars.len());
+    assert_eq!(
+        list.cvars.get("sv_hostname").map(String::as_str),
+        Some("Odamex mock server")
+    );
+
+    let output = socket
+        .command_output("set sv_gravity 400", Duration::from_millis(200))
+        .await
+        .unwrap();
+    assert_eq!(output, "\"sv_gravity\" is now \"400\"\n");
+    let output = socket
+        .command_output("sv_gravity", Duration::from_millis(200))
+        .await
+        .unwrap();
+    assert_eq!(output, "\"sv_gravity\" is \"400\"\n");
+    socket.disconnect();
+}
+
+#[tokio::test]
+async fn players_and_kicking() {
+    let server = start("").await;
+    let socket = RCONSocket::connect(&server_config(&server, "", false)).unwrap();
+    wait_for_state(&socket, ConnectionState::LoggedIn).await;
+
+    let list = players::fetch(&socket).await.unwrap();
+    assert_eq!(list, MockConfig::default().players);
+
+    let kick = PlayerAction::Kick {
+        reason: "be nice".to_string(),
+    };
+    let output = socket
+        .command_output(&kick.command(list[0].id), Duration::from_millis(200))
+        .await
+        .unwrap();
+    assert_eq!(output, "Player was kicked: be nice\n");
+    let list = players::fetch(&socket).await.unwrap();
+    let names: Vec<&str> = list.iter().map(|player| player.name.as_str()).collect();
+    assert_eq!(names, vec!["Player2"]);
+    socket.disconnect();
+}
+
+#[tokio::test]
+async fn server_info_and_ping() {
+    let server = start("").await;
+    let socket = RCONSocket::connect(&server_config(&server, "", false)).unwrap();
+    let mut events = socket.subscribe();
+
+    let info = loop {
+        if let ConnectionEvent::ServerInfo(info) = next_event(&mut events).await {
+            break info;
+        }
+    };
+    assert_eq!(info.hostname, "Odamex mock server");
+    assert_eq!(info.map, "MAP01");
+    assert_eq!(info.gametype, "Cooperative");
+    assert_eq!(info.maxplayers, 8);
+
+    let ping = status::ping(&socket).await.unwrap();
+    assert_eq!(ping.hostname.as_deref(), Some("Odamex mock server"));
+    assert_eq!(ping.map.as_deref(), Some("MAP01"));
+    assert!(ping.latency < TIMEOUT);
+
+    // the next ping sees the map change
+    socket
+        .command_output("map MAP07", Duration::from_millis(200))
+        .await
+        .unwrap();
+    let ping = status::ping(&socket).await.unwrap();
+    assert_eq!(ping.map.as_deref(), Some("MAP07"));
+    socket.disconnect();
+}
+
+#[tokio::test]
+async fn ban_list() {
+    let server = start("").await;
+    let socket = RCONSocket::connect(&server_config(&server, "", false)).unwrap();
+    wait_for_state(&socket, ConnectionState::LoggedIn).await;
+    let quiet = Duration::from_millis(200);
+
+    assert_eq!(
+        bans::fetch(&socket).await.unwrap(),
+        MockConfig::default().bans
+    );
+
+    let output = socket
+        .command_output(
+            &bans::add_command("10.0.*.*", "2 days", "team killing"),
+            quiet,
+        )
+        .await
+        .unwrap();
+    assert_eq!(output, "Added ban on 10.0.*.*\n");
+    let ban = PlayerAction::Ban {
+        length: String::new(),
+        reason: String::new(),
+    };
+    socket.command_output(&ban.command(2), quiet).await.unwrap();
+    socket
+        .command_output(&bans::remove_command(1), quiet)
+        .await
+        .unwrap();
+
+    let list = bans::fetch(&socket).await.unwrap();
+    let addresses: Vec<(usize, &str)> = list
+        .iter()
+        .map(|ban| (ban.index, ban.address.as_str()))
+        .collect();
+    assert_eq!(addresses, vec![(1, "10.0.*.*"), (2, "127.0.0.2")]);
+    assert_eq!(list[0].reason, "team killing");
+    assert!(list[0].expires.is_some());
+    assert_eq!(list[1].expires, None);
+    socket.disconnect();
+}
+
+#[tokio::test]
+async fn broadcast_to_several_servers() {
+    let first = start("").await;
+    let second = start("secret").await;
+    let servers = [
+        ServerConfig {
+            name: "first".to_string(),
+            ..server_config(&first, "", true)
+        },
+        ServerConfig {
+            name: "second".to_string(),
+            ..server_config(&second, "guess", true)
+        },
+        ServerConfig {
+            name: "third".to_string(),
+            ..server_config(&second, "secret", true)
+        },
+    ];
+
+    let outcomes =
+        odarcon::broadcast::broadcast(&servers, "sv_gravity", Duration::from_millis(200)).await;
+    let names: Vec<&str> = outcomes
+        .iter()
+        .map(|outcome| outcome.server.as_str())
+        .collect();
+    assert_eq!(names, vec!["first", "second", "third"]);
+    assert_eq!(
+        outcomes[0].output.as_ref().unwrap(),
+        "\"sv_gravity\" is \"800\"\n"
+    );
+    assert!(matches!(
+        outcomes[1].output,
+        Err(BroadcastError::LoginFailed(_))
+    ));
+    assert!(outcomes[2].output.is_ok());
+}
+
+#[tokio::test]
+async fn reconnects_after_drop() {
+    let server = start("").await;
+    let socket = RCONSocket::connect(&server_config(&server, "", true)).unwrap();
+    wait_for_state(&socket, ConnectionState::LoggedIn).await;
+    let mut events = socket.subscribe();
+
+    server.drop_connections();
+    loop {
+        if let ConnectionEvent::Reconnecting { attempt, .. } = next_event(&mut events).await {
+            assert_eq!(attempt, 1);
+            break;
+        }
+    }
+    loop {
+        if let ConnectionEvent::Authenticated = next_event(&mut events).await {
+            break;
+        }
+    }
+
+    // the drop command does the same from the client's side
+    socket.send(ClientMessageType::Command(DROP_COMMAND.to_string()));
+    loop {
+        if let ConnectionEvent::Reconnecting { .. } = next_event(&mut events).await {
+            break;
+        }
+    }
+    socket.disconnect();
+}
+
+#[tokio::test]
+async fn nothing_is_replayed_after_reconnecting() {
+    let server = start("").await;
+    let socket = RCONSocket::connect(&server_config(&server, "", true)).unwrap();
+    wait_for_state(&socket, ConnectionState::LoggedIn).await;
+    let mut events = socket.subscribe();
+
+    server.drop_connections();
+    loop {
+        if let ConnectionEvent::Reconnecting { .. } = next_event(&mut events).await {
+            break;
+        }
+    }
+    socket.send(ClientMessageType::Command("say while away".to_string()));
+    let request = socket.request(ClientMessageType::Command("players".to_string()));
+    let request = tokio::time::timeout(Duration::from_secs(2), request).await;
+    assert!(matches!(request, Ok(Err(RCONError::Disconnected))));
+    let mut dropped = false;
+    loop {
+        match next_event(&mut events).await {
+            ConnectionEvent::SendError(error) => dropped = error.contains("\"say while away\""),
+            ConnectionEvent::Authenticated => break,
+            _ => {}
+        }
+    }
+    assert!(dropped);
+
+    socket.send(ClientMessageType::Command("say back".to_string()));
+    loop {
+        if let ConnectionEvent::Print { text, .. } = next_event(&mut events).await {
+            assert_ne!(text, "say while away\n");
+            if text == "say back\n" {
+                break;
+            }
+        }
+    }
+    socket.disconnect();
+}